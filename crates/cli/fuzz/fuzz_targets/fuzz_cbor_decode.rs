@@ -0,0 +1,14 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rain_metadata::RainMetaDocumentV1Item;
+
+// cbor_decode (and unpacking of whatever it decodes) must return an error rather
+// than panic on any input
+fuzz_target!(|data: &[u8]| {
+    if let Ok(metas) = RainMetaDocumentV1Item::cbor_decode(data) {
+        for meta in metas {
+            let _ = meta.unpack();
+        }
+    }
+});
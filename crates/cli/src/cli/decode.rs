@@ -0,0 +1,123 @@
+use clap::Parser;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use alloy::primitives::hex;
+use serde_json::{json, Value};
+use crate::meta::{RainMetaDocumentV1Item, magic::KnownMagic};
+
+/// command for decoding rain meta, the inverse of build
+#[derive(Parser)]
+pub struct Decode {
+    /// Input path to the meta bytes, either binary or 0x prefixed hex. If not
+    /// specified, the input is read from stdin.
+    #[arg(short, long)]
+    input_path: Option<PathBuf>,
+    /// Output path. If not specified, the output is written to stdout.
+    #[arg(short, long)]
+    output_path: Option<PathBuf>,
+}
+
+/// Describes each item of the given meta bytes as json, i.e. magic, content type,
+/// encoding and language, payload length and for text metas the unpacked text.
+/// Hex input (0x prefixed) is decoded before the cbor decoding.
+pub fn decode_bytes(data: &[u8]) -> anyhow::Result<Value> {
+    let bytes = match std::str::from_utf8(data).map(|v| v.trim()) {
+        Ok(text) if text.starts_with("0x") => hex::decode(text)?,
+        _ => data.to_vec(),
+    };
+    let items = RainMetaDocumentV1Item::cbor_decode(&bytes)?
+        .into_iter()
+        .map(|item| {
+            let text = match item.magic {
+                KnownMagic::DotrainV1 | KnownMagic::RainlangV1 | KnownMagic::RainlangSourceV1 => {
+                    item.clone().unpack_into::<String>().ok()
+                }
+                _ => None,
+            };
+            let mut map = item.to_json_map();
+            map.remove("payload");
+            map.insert("payload_length".to_string(), json!(item.payload.len()));
+            map.insert("text".to_string(), json!(text));
+            Value::Object(map)
+        })
+        .collect();
+    Ok(Value::Array(items))
+}
+
+/// Decode rain meta bytes from a file or stdin and write the json description
+/// of its items to a file or stdout.
+pub fn decode(d: Decode) -> anyhow::Result<()> {
+    let data = match &d.input_path {
+        Some(input_path) => std::fs::read(input_path)?,
+        None => {
+            let mut data = vec![];
+            std::io::stdin().read_to_end(&mut data)?;
+            data
+        }
+    };
+    let output = serde_json::to_string_pretty(&decode_bytes(&data)?)?;
+    if let Some(output_path) = d.output_path {
+        std::fs::write(output_path, output)?
+    } else {
+        std::io::stdout().write_all(output.as_bytes())?
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::build::{build_bytes, BuildItem};
+    use crate::meta::{ContentEncoding, ContentLanguage, ContentType};
+
+    #[test]
+    fn test_decode_built_bytes() -> anyhow::Result<()> {
+        let items = vec![
+            BuildItem {
+                data: "#main _: 1;".as_bytes().to_vec(),
+                magic: KnownMagic::DotrainV1,
+                content_type: ContentType::OctetStream,
+                content_encoding: ContentEncoding::Deflate,
+                content_language: ContentLanguage::En,
+            },
+            BuildItem {
+                data: "[]".as_bytes().to_vec(),
+                magic: KnownMagic::SolidityAbiV2,
+                content_type: ContentType::Json,
+                content_encoding: ContentEncoding::None,
+                content_language: ContentLanguage::None,
+            },
+        ];
+        let bytes = build_bytes(KnownMagic::RainMetaDocumentV1, items)?;
+        let payload_length = RainMetaDocumentV1Item::cbor_decode(&bytes)?[0]
+            .payload
+            .len();
+        let expected = json!([
+            {
+                "magic": "dotrain-v1",
+                "content_type": "application/octet-stream",
+                "content_encoding": "deflate",
+                "content_language": "en",
+                "payload_length": payload_length,
+                "text": "#main _: 1;",
+            },
+            {
+                "magic": "solidity-abi-v2",
+                "content_type": "application/json",
+                "content_encoding": null,
+                "content_language": null,
+                "payload_length": 2,
+                "text": null,
+            }
+        ]);
+
+        assert_eq!(decode_bytes(&bytes)?, expected);
+        // same result for hex input
+        assert_eq!(
+            decode_bytes(hex::encode_prefixed(&bytes).as_bytes())?,
+            expected
+        );
+
+        Ok(())
+    }
+}
@@ -6,11 +6,11 @@ use clap::Subcommand;
 #[derive(Subcommand)]
 pub enum Magic {
     /// Print all known magic numbers.
-    Ls,
+    Ls(ls::Ls),
 }
 
 pub fn dispatch(magic: Magic) -> anyhow::Result<()> {
     match magic {
-        Magic::Ls => ls::ls(),
+        Magic::Ls(args) => ls::ls(args),
     }
 }
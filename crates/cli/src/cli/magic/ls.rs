@@ -1,9 +1,22 @@
+use clap::Parser;
 use strum::IntoEnumIterator;
 use crate::meta::magic::KnownMagic;
 
-pub fn ls() -> anyhow::Result<()> {
+/// command for listing known magic numbers
+#[derive(Parser)]
+pub struct Ls {
+    /// Print the magic numbers as solidity bytes8 literals.
+    #[arg(long)]
+    solidity: bool,
+}
+
+pub fn ls(ls: Ls) -> anyhow::Result<()> {
     for magic in KnownMagic::iter() {
-        println!("{:#x} {}", magic as u64, magic);
+        if ls.solidity {
+            println!("{} {}", magic.to_solidity_literal(), magic);
+        } else {
+            println!("{:#x} {}", magic as u64, magic);
+        }
     }
     Ok(())
 }
@@ -6,6 +6,7 @@
 
 pub mod solc;
 pub mod build;
+pub mod decode;
 pub mod magic;
 pub mod schema;
 pub mod output;
@@ -29,25 +30,27 @@ pub enum Meta {
     #[command(subcommand)]
     Magic(magic::Magic),
     Build(build::Build),
+    Decode(decode::Decode),
     #[command(subcommand)]
     Solc(solc::Solc),
     #[command(subcommand)]
     Subgraph(subgraph::Sg),
 }
 
-pub fn dispatch(meta: Meta) -> anyhow::Result<()> {
+pub async fn dispatch(meta: Meta) -> anyhow::Result<()> {
     match meta {
         Meta::Build(build) => build::build(build),
+        Meta::Decode(decode) => decode::decode(decode),
         Meta::Solc(solc) => solc::dispatch(solc),
         Meta::Subgraph(sg) => subgraph::dispatch(sg),
         Meta::Magic(magic) => magic::dispatch(magic),
         Meta::Schema(schema) => schema::dispatch(schema),
-        Meta::Validate(validate) => validate::validate(validate),
+        Meta::Validate(validate) => validate::validate(validate).await,
     }
 }
 
-pub fn main() -> anyhow::Result<()> {
+pub async fn main() -> anyhow::Result<()> {
     tracing::subscriber::set_global_default(tracing_subscriber::fmt::Subscriber::new())?;
     let cli = Cli::parse();
-    dispatch(cli.meta)
+    dispatch(cli.meta).await
 }
@@ -1,4 +1,5 @@
 use clap::Parser;
+use serde_json::{Map, Value};
 use std::path::PathBuf;
 use schemars::schema_for;
 use crate::meta::KnownMeta;
@@ -19,12 +20,20 @@ pub struct Show {
     /// If true the schema will be pretty printed. Defaults to false.
     #[arg(short, long)]
     pretty_print: bool,
+    /// If true all `$ref`s will be replaced by the schemas they point to so the
+    /// output has no definitions. Recursive references are replaced by an empty
+    /// (permissive) schema.
+    #[arg(long)]
+    inline_refs: bool,
 }
 
 pub fn show(s: Show) -> anyhow::Result<()> {
     let schema_json = match s.schema {
         KnownMeta::OpV1 => schema_for!(crate::meta::types::op::v1::OpMeta),
         KnownMeta::AuthoringMetaV1 => schema_for!(crate::meta::types::authoring::v1::AuthoringMeta),
+        KnownMeta::AuthoringMetaV2 => {
+            schema_for!(crate::meta::types::authoring::v2::AuthoringMetaV2)
+        }
         KnownMeta::SolidityAbiV2 => {
             schema_for!(crate::meta::types::solidity_abi::v2::SolidityAbiMeta)
         }
@@ -33,6 +42,10 @@ pub fn show(s: Show) -> anyhow::Result<()> {
         }
         other => return Err(anyhow::anyhow!("Unsupported for {} meta", other)),
     };
+    let mut schema_json = serde_json::to_value(&schema_json)?;
+    if s.inline_refs {
+        schema_json = inline_refs(schema_json);
+    }
     let schema_string = if s.pretty_print {
         serde_json::to_string_pretty(&schema_json)?
     } else {
@@ -45,3 +58,80 @@ pub fn show(s: Show) -> anyhow::Result<()> {
         schema_string.as_bytes(),
     )
 }
+
+/// replaces all `$ref`s of the given root schema with their definitions and removes
+/// the definitions, recursive references are replaced by an empty schema
+pub fn inline_refs(mut schema: Value) -> Value {
+    let definitions = match schema.as_object_mut() {
+        Some(obj) => match obj.remove("definitions") {
+            Some(Value::Object(definitions)) => definitions,
+            _ => Map::new(),
+        },
+        None => Map::new(),
+    };
+    inline(schema, &definitions, &mut vec![])
+}
+
+fn inline(value: Value, definitions: &Map<String, Value>, stack: &mut Vec<String>) -> Value {
+    match value {
+        Value::Object(mut obj) => {
+            if let Some(Value::String(reference)) = obj.get("$ref") {
+                let name = reference
+                    .strip_prefix("#/definitions/")
+                    .unwrap_or(reference)
+                    .to_string();
+                obj.remove("$ref");
+                let mut resolved = match definitions.get(&name) {
+                    Some(definition) if !stack.contains(&name) => {
+                        stack.push(name);
+                        let resolved = inline(definition.clone(), definitions, stack);
+                        stack.pop();
+                        resolved
+                    }
+                    _ => Value::Object(Map::new()),
+                };
+                // keep any sibling keys of the $ref, e.g. description
+                if let Value::Object(resolved_obj) = &mut resolved {
+                    for (key, value) in obj {
+                        resolved_obj.insert(key, inline(value, definitions, stack));
+                    }
+                }
+                resolved
+            } else {
+                Value::Object(
+                    obj.into_iter()
+                        .map(|(key, value)| (key, inline(value, definitions, stack)))
+                        .collect(),
+                )
+            }
+        }
+        Value::Array(array) => Value::Array(
+            array
+                .into_iter()
+                .map(|value| inline(value, definitions, stack))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inline_refs() -> anyhow::Result<()> {
+        let schema = serde_json::to_value(schema_for!(
+            crate::meta::types::solidity_abi::v2::SolidityAbiMeta
+        ))?;
+        assert!(serde_json::to_string(&schema)?.contains("$ref"));
+
+        let inlined = inline_refs(schema);
+        let inlined_string = serde_json::to_string(&inlined)?;
+        assert!(!inlined_string.contains("$ref"));
+        assert!(!inlined_string.contains("definitions"));
+        assert_eq!(inlined["type"], "array");
+
+        Ok(())
+    }
+}
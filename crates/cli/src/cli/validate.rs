@@ -1,6 +1,12 @@
 use clap::Parser;
 use std::path::PathBuf;
-use crate::meta::KnownMeta;
+use std::str::FromStr;
+use alloy::primitives::U256;
+use anyhow::anyhow;
+use rain_metaboard_subgraph::metaboard_client::{MetaboardSubgraphClient, MetaboardSubgraphClientError};
+use rain_metaboard_subgraph::types::metas::BigInt;
+use url::Url;
+use crate::meta::{KnownMeta, RainMetaDocumentV1Item};
 
 /// command for validating a meta
 #[derive(Parser)]
@@ -10,13 +16,129 @@ pub struct Validate {
     meta: KnownMeta,
     /// The input path to the json serialized metadata to validate against the
     /// known schema.
-    #[arg(short, long)]
-    input_path: PathBuf,
+    #[arg(short, long, required_unless_present = "from_subgraph")]
+    input_path: Option<PathBuf>,
+    /// Metaboard subgraph url to fetch the metas of the subject from, the fetched
+    /// metas are validated instead of the input path.
+    #[arg(long, requires = "subject", conflicts_with = "input_path")]
+    from_subgraph: Option<Url>,
+    /// Hex subject of the metas to fetch from the metaboard subgraph.
+    #[arg(long, requires = "from_subgraph")]
+    subject: Option<String>,
 }
 
-pub fn validate(v: Validate) -> anyhow::Result<()> {
-    let data: Vec<u8> = std::fs::read(v.input_path)?;
+pub async fn validate(v: Validate) -> anyhow::Result<()> {
+    if let (Some(url), Some(subject)) = (v.from_subgraph, v.subject) {
+        return validate_subject(v.meta, url, &subject).await;
+    }
+    let input_path = v.input_path.ok_or(anyhow!("missing input path"))?;
+    let data: Vec<u8> = std::fs::read(input_path)?;
     // If we can normalize the input data then it is valid.
     let _normalized = v.meta.normalize(&data)?;
     Ok(())
 }
+
+/// Fetches all the metas of the given hex subject from a metaboard subgraph and
+/// validates their items of the given known meta, errors if there are no metas for
+/// the subject, if any item fails to unpack or if none of the items is of the meta.
+pub async fn validate_subject(meta: KnownMeta, url: Url, subject: &str) -> anyhow::Result<()> {
+    let client = MetaboardSubgraphClient::new(url);
+    let metas = match client
+        .get_all_metabytes_by_subject(&BigInt(U256::from_str(subject)?.to_string()))
+        .await
+    {
+        Err(MetaboardSubgraphClientError::Empty(_)) => {
+            return Err(anyhow!("no metas found for subject {}", subject))
+        }
+        metas => metas?,
+    };
+    let mut validated = 0;
+    for (i, bytes) in metas.iter().enumerate() {
+        for item in RainMetaDocumentV1Item::cbor_decode(bytes)? {
+            let data = item.unpack().map_err(|e| {
+                anyhow!("failed to unpack {} item of meta {}: {}", item.magic, i, e)
+            })?;
+            if KnownMeta::try_from(item.magic).ok() == Some(meta) {
+                // If we can normalize the unpacked data then it is valid.
+                meta.normalize(&data)?;
+                validated += 1;
+            }
+        }
+    }
+    if validated == 0 {
+        return Err(anyhow!("no {} meta found for subject {}", meta, subject));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::hex;
+    use httpmock::{Method::POST, MockServer};
+    use crate::meta::{
+        magic::KnownMagic,
+        types::authoring::v1::{AuthoringMeta, AuthoringMetaItem},
+    };
+
+    #[tokio::test]
+    async fn test_validate_subject() -> anyhow::Result<()> {
+        let authoring_meta = AuthoringMeta(vec![AuthoringMetaItem {
+            word: "stack".to_string(),
+            operand_parser_offset: 16,
+            description: "Copies an existing value from the stack.".to_string(),
+        }]);
+        let meta_bytes = RainMetaDocumentV1Item::cbor_encode_seq(
+            &vec![RainMetaDocumentV1Item::authoring(&authoring_meta)?],
+            KnownMagic::RainMetaDocumentV1,
+        )?;
+
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method(POST).path("/").body_contains("\"1\"");
+            then.status(200).json_body_obj(&serde_json::json!({
+                "data": {
+                    "metaV1S": [{
+                        "meta": hex::encode_prefixed(&meta_bytes),
+                        "metaHash": "0x00",
+                        "sender": "0x00",
+                        "id": "0x00",
+                        "metaBoard": {
+                            "id": "0x00",
+                            "metas": [],
+                            "address": "0x00",
+                        },
+                        "subject": "1",
+                    }]
+                }
+            }));
+        });
+        server.mock(|when, then| {
+            when.method(POST).path("/").body_contains("\"2\"");
+            then.status(200).json_body_obj(&serde_json::json!({
+                "data": { "metaV1S": [] }
+            }));
+        });
+        let url: Url = server.url("/").parse()?;
+
+        validate(Validate {
+            meta: KnownMeta::AuthoringMetaV1,
+            input_path: None,
+            from_subgraph: Some(url.clone()),
+            subject: Some("0x01".to_string()),
+        })
+        .await?;
+
+        let error = validate_subject(KnownMeta::AuthoringMetaV1, url.clone(), "0x02")
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "no metas found for subject 0x02");
+
+        let error = validate_subject(KnownMeta::OpV1, url, "0x01")
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "no op-v1 meta found for subject 0x01");
+
+        Ok(())
+    }
+}
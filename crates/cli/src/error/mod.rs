@@ -1,9 +1,12 @@
-use std::{string::FromUtf8Error, str::Utf8Error};
+use std::{string::FromUtf8Error, str::Utf8Error, io};
+use rain_metaboard_subgraph::metaboard_client::MetaboardSubgraphClientError;
+use crate::meta::KnownMagic;
 
 /// Covers all errors variants of Rain Metadat lib functionalities
 #[derive(Debug)]
 pub enum Error {
     CorruptMeta,
+    Timeout,
     InvalidHash,
     UnknownMeta,
     UnknownMagic,
@@ -11,7 +14,34 @@ pub enum Error {
     UnsupportedMeta,
     BiggerThan32Bytes,
     UnsupportedNetwork,
+    DecompressionLimitExceeded,
+    InvalidDataUri,
     InflateError(String),
+    /// payload doesn't match the content type or the magic number of a meta
+    ContentTypeMismatch(String),
+    /// index of the fetched meta that failed to cbor decode and the decode error
+    InvalidMetaDocument(usize, Box<Error>),
+    /// failed to convert an unpacked meta of the given magic into the requested type
+    UnpackFailed {
+        magic: KnownMagic,
+        reason: String,
+    },
+    /// byte length of the address list payload
+    InvalidAddressListLength(usize),
+    /// path of the offending field of a meta and why it is invalid
+    InvalidMetaField {
+        field: String,
+        reason: String,
+    },
+    /// semantic problem of an op meta that passes the schema checks
+    InvalidOpMeta(String),
+    /// authoring meta word that is not ascii so may not round trip through bytes32
+    InvalidWordEncoding(String),
+    /// max allowed nesting depth of cbor data that was exceeded
+    MaxDepthExceeded(usize),
+    /// words only present in op meta, words only present in authoring meta
+    WordSetMismatch(Vec<String>, Vec<String>),
+    IoError(io::Error),
     Utf8Error(Utf8Error),
     FromUtf8Error(FromUtf8Error),
     ReqwestError(reqwest::Error),
@@ -20,12 +50,15 @@ pub enum Error {
     AbiCoderError(alloy::sol_types::Error),
     ValidationErrors(validator::ValidationErrors),
     DecodeHexStringError(alloy::primitives::hex::FromHexError),
+    Base64DecodeError(base64::DecodeError),
+    MetaboardSubgraphClientError(MetaboardSubgraphClientError),
 }
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Error::CorruptMeta => f.write_str("corrupt meta"),
+            Error::Timeout => f.write_str("request timed out"),
             Error::UnknownMeta => f.write_str("unknown meta"),
             Error::UnknownMagic => f.write_str("unknown magic"),
             Error::UnsupportedMeta => f.write_str("unsupported meta"),
@@ -34,11 +67,43 @@ impl std::fmt::Display for Error {
             Error::UnsupportedNetwork => {
                 f.write_str("no rain subgraph is deployed for this network")
             }
+            Error::DecompressionLimitExceeded => f.write_str("decoded data exceeds the size limit"),
+            Error::InvalidDataUri => f.write_str("invalid cbor data uri"),
             Error::BiggerThan32Bytes => {
                 f.write_str("unexpected input size, must be 32 bytes or less")
             }
             Error::ReqwestError(v) => write!(f, "{}", v),
             Error::InflateError(v) => write!(f, "{}", v),
+            Error::ContentTypeMismatch(v) => write!(f, "{}", v),
+            Error::InvalidMetaDocument(i, e) => {
+                write!(f, "fetched meta at index {} is not a valid meta document: {}", i, e)
+            }
+            Error::UnpackFailed { magic, reason } => {
+                write!(f, "failed to unpack {} meta: {}", magic, reason)
+            }
+            Error::InvalidMetaField { field, reason } => {
+                write!(f, "invalid {}: {}", field, reason)
+            }
+            Error::InvalidOpMeta(v) => write!(f, "invalid op meta: {}", v),
+            Error::InvalidWordEncoding(v) => write!(
+                f,
+                "word \"{}\" must be ascii to be safely packed into bytes32",
+                v
+            ),
+            Error::MaxDepthExceeded(v) => {
+                write!(f, "cbor data is nested deeper than the max depth of {}", v)
+            }
+            Error::InvalidAddressListLength(v) => write!(
+                f,
+                "invalid address list length {}, must be a multiple of 20 bytes",
+                v
+            ),
+            Error::WordSetMismatch(op, authoring) => write!(
+                f,
+                "op meta and authoring meta word sets mismatch, missing in authoring meta: {:?}, missing in op meta: {:?}",
+                op, authoring
+            ),
+            Error::IoError(v) => write!(f, "{}", v),
             Error::Utf8Error(v) => write!(f, "{}", v),
             Error::AbiCoderError(v) => write!(f, "{}", v),
             Error::SerdeCborError(v) => write!(f, "{}", v),
@@ -46,12 +111,44 @@ impl std::fmt::Display for Error {
             Error::FromUtf8Error(v) => write!(f, "{}", v),
             Error::DecodeHexStringError(v) => write!(f, "{}", v),
             Error::ValidationErrors(v) => write!(f, "{}", v),
+            Error::Base64DecodeError(v) => write!(f, "{}", v),
+            Error::MetaboardSubgraphClientError(v) => write!(f, "{}", v),
         }
     }
 }
 
 impl std::error::Error for Error {}
 
+impl Error {
+    /// if the error is caused by network failures, timeouts or server error (and 429)
+    /// responses and retrying the same operation might succeed, decode and corrupt data
+    /// errors are not retryable unless the response had a retryable status
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::Timeout => true,
+            Error::ReqwestError(e) => {
+                if let Some(status) = e.status() {
+                    status.is_server_error() || status.as_u16() == 429
+                } else {
+                    !(e.is_decode() || e.is_builder() || e.is_redirect())
+                }
+            }
+            Error::IoError(e) => matches!(
+                e.kind(),
+                io::ErrorKind::TimedOut
+                    | io::ErrorKind::Interrupted
+                    | io::ErrorKind::ConnectionReset
+                    | io::ErrorKind::ConnectionAborted
+                    | io::ErrorKind::ConnectionRefused
+                    | io::ErrorKind::NotConnected
+                    | io::ErrorKind::BrokenPipe
+                    | io::ErrorKind::UnexpectedEof
+            ),
+            _ => false,
+        }
+    }
+}
+
 impl From<serde_json::Error> for Error {
     fn from(value: serde_json::Error) -> Self {
         Error::SerdeJsonError(value)
@@ -64,6 +161,12 @@ impl From<serde_cbor::Error> for Error {
     }
 }
 
+impl From<io::Error> for Error {
+    fn from(value: io::Error) -> Self {
+        Error::IoError(value)
+    }
+}
+
 impl From<FromUtf8Error> for Error {
     fn from(value: FromUtf8Error) -> Self {
         Error::FromUtf8Error(value)
@@ -76,6 +179,18 @@ impl From<Utf8Error> for Error {
     }
 }
 
+impl From<base64::DecodeError> for Error {
+    fn from(value: base64::DecodeError) -> Self {
+        Error::Base64DecodeError(value)
+    }
+}
+
+impl From<MetaboardSubgraphClientError> for Error {
+    fn from(value: MetaboardSubgraphClientError) -> Self {
+        Error::MetaboardSubgraphClientError(value)
+    }
+}
+
 impl From<validator::ValidationErrors> for Error {
     fn from(value: validator::ValidationErrors) -> Self {
         Error::ValidationErrors(value)
@@ -87,3 +202,49 @@ impl From<alloy::sol_types::Error> for Error {
         Error::AbiCoderError(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_is_retryable() {
+        // nothing listens on port 1 so this fails to connect
+        let reqwest_error = reqwest::get("http://127.0.0.1:1").await.unwrap_err();
+        assert!(Error::ReqwestError(reqwest_error).is_retryable());
+
+        let timeout_error = io::Error::new(io::ErrorKind::TimedOut, "timed out");
+        assert!(Error::IoError(timeout_error).is_retryable());
+
+        assert!(!Error::CorruptMeta.is_retryable());
+        assert!(!Error::InvalidHash.is_retryable());
+        let cbor_error = serde_cbor::from_slice::<u64>(&[0xff]).unwrap_err();
+        assert!(!Error::SerdeCborError(cbor_error).is_retryable());
+
+        // non json bodies are retryable only with a server error or 429 status
+        let server = httpmock::MockServer::start_async().await;
+        for (path, status, retryable) in [
+            ("/502", 502, true),
+            ("/503", 503, true),
+            ("/429", 429, true),
+            ("/404", 404, false),
+            ("/200", 200, false),
+        ] {
+            server
+                .mock_async(|when, then| {
+                    when.path(path);
+                    then.status(status).body("<html>gateway error</html>");
+                })
+                .await;
+            let error = match reqwest::get(server.url(path))
+                .await
+                .unwrap()
+                .error_for_status()
+            {
+                Ok(response) => response.json::<serde_json::Value>().await.unwrap_err(),
+                Err(e) => e,
+            };
+            assert_eq!(Error::ReqwestError(error).is_retryable(), retryable);
+        }
+    }
+}
@@ -7,11 +7,11 @@ pub(crate) mod subgraph;
 #[cfg(feature = "tokio-full")]
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    cli::main()
+    cli::main().await
 }
 
 #[cfg(not(feature = "tokio-full"))]
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> anyhow::Result<()> {
-    cli::main()
+    cli::main().await
 }
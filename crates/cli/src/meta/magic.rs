@@ -39,16 +39,70 @@ pub enum KnownMagic {
     AddressList = 0xffb2637608c09e38,
 }
 
+/// Families of the known magic numbers, e.g. for grouping metas in UIs
+#[derive(
+    serde::Serialize,
+    Clone,
+    Copy,
+    strum::EnumIter,
+    strum::Display,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    serde::Deserialize,
+)]
+#[strum(serialize_all = "kebab-case")]
+#[serde(rename_all = "kebab-case")]
+pub enum MagicFamily {
+    Dotrain,
+    Rainlang,
+    Authoring,
+    Deployer,
+    InterpreterCaller,
+    Other,
+}
+
 impl KnownMagic {
+    /// the family this magic number belongs to
+    pub fn family(&self) -> MagicFamily {
+        match self {
+            KnownMagic::DotrainV1 => MagicFamily::Dotrain,
+            KnownMagic::RainlangV1 | KnownMagic::RainlangSourceV1 => MagicFamily::Rainlang,
+            KnownMagic::OpMetaV1 | KnownMagic::AuthoringMetaV1 | KnownMagic::AuthoringMetaV2 => {
+                MagicFamily::Authoring
+            }
+            KnownMagic::ExpressionDeployerV2BytecodeV1 => MagicFamily::Deployer,
+            KnownMagic::InterpreterCallerMetaV1 => MagicFamily::InterpreterCaller,
+            KnownMagic::RainMetaDocumentV1
+            | KnownMagic::SolidityAbiV2
+            | KnownMagic::AddressList => MagicFamily::Other,
+        }
+    }
+
     pub fn to_prefix_bytes(&self) -> [u8; 8] {
         // Use big endian here as the magic numbers are for binary data prefixes.
         (*self as u64).to_be_bytes()
     }
+
+    /// formats the magic number as a solidity bytes8 literal, e.g. bytes8(0xff0a89c674ee7874)
+    pub fn to_solidity_literal(&self) -> String {
+        format!("bytes8({:#018x})", *self as u64)
+    }
+}
+
+/// checks if the value is in the reserved rain magic range, i.e. its top byte is 0xff,
+/// useful for quickly rejecting obviously non-rain prefixes before a full lookup
+pub fn is_rain_magic_prefix(value: u64) -> bool {
+    value >> 56 == 0xff
 }
 
 impl TryFrom<u64> for KnownMagic {
     type Error = crate::error::Error;
     fn try_from(value: u64) -> Result<Self, Self::Error> {
+        if !is_rain_magic_prefix(value) {
+            return Err(crate::error::Error::UnknownMagic);
+        }
         match value {
             v if v == KnownMagic::OpMetaV1 as u64 => Ok(KnownMagic::OpMetaV1),
             v if v == KnownMagic::DotrainV1 as u64 => Ok(KnownMagic::DotrainV1),
@@ -72,8 +126,49 @@ impl TryFrom<u64> for KnownMagic {
 
 #[cfg(test)]
 mod tests {
-    use super::KnownMagic;
+    use super::{is_rain_magic_prefix, KnownMagic, MagicFamily};
     use alloy::primitives::hex;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_is_rain_magic_prefix() {
+        for magic in KnownMagic::iter() {
+            assert!(is_rain_magic_prefix(magic as u64));
+        }
+        assert!(is_rain_magic_prefix(0xff00000000000000));
+        assert!(is_rain_magic_prefix(u64::MAX));
+
+        assert!(!is_rain_magic_prefix(0));
+        assert!(!is_rain_magic_prefix(0xfeffffffffffffff));
+        assert!(!is_rain_magic_prefix(0x00ff0a89c674ee78));
+        assert!(KnownMagic::try_from(0x0a89c674ee7874ff).is_err());
+    }
+
+    #[test]
+    fn test_family() {
+        assert_eq!(KnownMagic::DotrainV1.family(), MagicFamily::Dotrain);
+        assert_eq!(KnownMagic::RainlangV1.family(), MagicFamily::Rainlang);
+        assert_eq!(KnownMagic::RainlangSourceV1.family(), MagicFamily::Rainlang);
+        assert_eq!(KnownMagic::AuthoringMetaV2.family(), MagicFamily::Authoring);
+        assert_eq!(
+            KnownMagic::ExpressionDeployerV2BytecodeV1.family(),
+            MagicFamily::Deployer
+        );
+        assert_eq!(KnownMagic::AddressList.family(), MagicFamily::Other);
+        assert_eq!(
+            KnownMagic::InterpreterCallerMetaV1.family(),
+            MagicFamily::InterpreterCaller
+        );
+        assert_eq!(MagicFamily::Dotrain.to_string(), "dotrain");
+        assert_eq!(
+            MagicFamily::InterpreterCaller.to_string(),
+            "interpreter-caller"
+        );
+        assert_eq!(
+            serde_json::to_string(&MagicFamily::InterpreterCaller).unwrap(),
+            "\"interpreter-caller\""
+        );
+    }
 
     #[test]
     fn test_rain_meta_document_v1() {
@@ -81,6 +176,10 @@ mod tests {
         let magic_number_after_prefix = magic_number.to_prefix_bytes();
 
         assert_eq!(hex::encode(magic_number_after_prefix), "ff0a89c674ee7874");
+        assert_eq!(
+            magic_number.to_solidity_literal(),
+            "bytes8(0xff0a89c674ee7874)"
+        );
     }
 
     #[test]
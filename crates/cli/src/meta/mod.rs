@@ -1,13 +1,39 @@
 use super::error::Error;
 use super::subgraph::KnownSubgraphs;
-use alloy::primitives::{hex, keccak256};
-use futures::future;
+use alloy::primitives::{hex, keccak256, Bytes, Keccak256};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use flate2::{
+    bufread::ZlibDecoder,
+    read::{DeflateDecoder, MultiGzDecoder},
+    write::GzEncoder,
+    Compression,
+};
+use futures_timer::Delay;
+use futures::{
+    future,
+    stream::{self, FuturesUnordered, StreamExt},
+};
 use graphql_client::GraphQLQuery;
+use rayon::prelude::*;
 use rain_metadata_bindings::IDescribedByMetaV1;
+use rain_metaboard_subgraph::metaboard_client::{MetaboardSubgraphClient, MetaboardSubgraphClientError};
+use rain_metaboard_subgraph::types::metas::BigInt;
 use reqwest::Client;
 use serde::de::{Deserialize, Deserializer, Visitor};
 use serde::ser::{Serialize, SerializeMap, Serializer};
-use std::{collections::HashMap, convert::TryFrom, fmt::Debug, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    convert::TryFrom,
+    fmt::Debug,
+    io::{Read, Write},
+    ops::Range,
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard,
+    },
+    time::{Duration, SystemTime},
+};
 use strum::{EnumIter, EnumString};
 use types::authoring::v1::AuthoringMeta;
 use alloy::sol_types::private::Address;
@@ -22,6 +48,19 @@ pub mod types;
 pub use magic::*;
 pub use query::*;
 
+/// default max size of decoded payloads, 64 MiB
+pub const DEFAULT_DECODE_LIMIT: usize = 64 * 1024 * 1024;
+
+/// default max number of concurrent subgraph searches of Store::update_many()
+pub const DEFAULT_UPDATE_CONCURRENCY: usize = 16;
+
+/// max nesting depth of arrays, maps and tags in decoded cbor data, checked before
+/// decoding so deeply nested input can't exhaust the stack
+pub const MAX_CBOR_NESTING_DEPTH: usize = 64;
+
+/// prefix of a cbor data uri, see RainMetaDocumentV1Item::to_data_uri()
+pub const CBOR_DATA_URI_PREFIX: &str = "data:application/cbor;base64,";
+
 /// All known meta identifiers
 #[derive(Copy, Clone, EnumString, EnumIter, strum::Display, Debug, PartialEq)]
 #[strum(serialize_all = "kebab-case")]
@@ -97,32 +136,167 @@ pub enum ContentType {
 #[serde(rename_all = "kebab-case")]
 #[strum(serialize_all = "kebab-case")]
 pub enum ContentEncoding {
+    /// no encoding, the encoding key is omitted from the cbor encoded meta map
     None,
+    /// no encoding either but the encoding key is written to the cbor encoded meta map,
+    /// so it results in a different subject than None, see canonicalize_encoding()
     Identity,
     Deflate,
+    Gzip,
+    #[serde(rename = "br")]
+    #[strum(serialize = "br")]
+    Brotli,
+    Zstd,
 }
 
+/// gzip member header magic bytes
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+/// zstd frame magic bytes
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
 impl ContentEncoding {
+    /// collapses Identity to None as they encode to the same bytes, so items that only
+    /// differ in these two produce the same subject once canonicalized
+    pub fn canonicalize_encoding(&self) -> ContentEncoding {
+        match self {
+            ContentEncoding::Identity => ContentEncoding::None,
+            other => *other,
+        }
+    }
+
     /// encode the data based on the variant
     pub fn encode(&self, data: &[u8]) -> Vec<u8> {
         match self {
             ContentEncoding::None | ContentEncoding::Identity => data.to_vec(),
             ContentEncoding::Deflate => deflate::deflate_bytes_zlib(data),
+            ContentEncoding::Gzip => {
+                let mut encoder = GzEncoder::new(vec![], Compression::default());
+                // writing into a vec never fails
+                let _ = encoder.write_all(data);
+                encoder.finish().unwrap_or_default()
+            }
+            ContentEncoding::Brotli => {
+                let mut encoder = brotli::CompressorWriter::new(vec![], 4096, 11, 22);
+                // writing into a vec never fails
+                let _ = encoder.write_all(data);
+                encoder.into_inner()
+            }
+            ContentEncoding::Zstd => {
+                ruzstd::encoding::compress_to_vec(data, ruzstd::encoding::CompressionLevel::Fastest)
+            }
         }
     }
 
-    /// decode the data based on the variant
+    /// decode the data based on the variant, the decoded output is limited to
+    /// DEFAULT_DECODE_LIMIT bytes, see decode_with_limit()
     pub fn decode(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
-        Ok(match self {
-            ContentEncoding::None | ContentEncoding::Identity => data.to_vec(),
-            ContentEncoding::Deflate => match inflate::inflate_bytes_zlib(data) {
-                Ok(v) => v,
-                Err(error) => match inflate::inflate_bytes(data) {
-                    Ok(v) => v,
-                    Err(_) => Err(Error::InflateError(error))?,
-                },
+        self.decode_with_limit(data, DEFAULT_DECODE_LIMIT)
+    }
+
+    /// decode the data based on the variant, inflating is done as a stream and bails
+    /// out with DecompressionLimitExceeded error once the output exceeds max_output
+    pub fn decode_with_limit(&self, data: &[u8], max_output: usize) -> Result<Vec<u8>, Error> {
+        match self {
+            ContentEncoding::None | ContentEncoding::Identity => Ok(data.to_vec()),
+            ContentEncoding::Deflate => match inflate_zlib_streams(data, max_output) {
+                Err(Error::InflateError(error)) => {
+                    match read_limited(DeflateDecoder::new(data), max_output) {
+                        Err(Error::InflateError(_)) => Err(Error::InflateError(error)),
+                        other => other,
+                    }
+                }
+                other => other,
             },
-        })
+            ContentEncoding::Gzip => {
+                match read_limited(MultiGzDecoder::new(data), max_output) {
+                    // fallback to zlib and raw deflate in case the gzip header is off
+                    Err(Error::InflateError(error)) => {
+                        match ContentEncoding::Deflate.decode_with_limit(data, max_output) {
+                            Err(Error::InflateError(_)) => Err(Error::InflateError(error)),
+                            other => other,
+                        }
+                    }
+                    other => other,
+                }
+            }
+            ContentEncoding::Brotli => {
+                read_limited(brotli::Decompressor::new(data, 4096), max_output)
+            }
+            ContentEncoding::Zstd => read_limited(
+                ruzstd::decoding::StreamingDecoder::new(data)
+                    .map_err(|e| Error::InflateError(e.to_string()))?,
+                max_output,
+            ),
+        }
+    }
+
+    /// decode the data without knowing its encoding, gzip, zstd and zlib data are
+    /// detected by their headers, anything else is tried as brotli and then goes
+    /// through the deflate fallbacks
+    pub fn decode_autodetect(data: &[u8]) -> Result<Vec<u8>, Error> {
+        if data.starts_with(&ZSTD_MAGIC) {
+            return ContentEncoding::Zstd.decode(data);
+        }
+        if data.starts_with(&GZIP_MAGIC) {
+            return ContentEncoding::Gzip.decode(data);
+        }
+        // brotli has no header, so a brotli stream can pass the zlib header check by chance
+        let (first, second) = if is_zlib_header(data) {
+            (ContentEncoding::Deflate, ContentEncoding::Brotli)
+        } else {
+            (ContentEncoding::Brotli, ContentEncoding::Deflate)
+        };
+        match first.decode(data) {
+            Err(Error::InflateError(error)) => match second.decode(data) {
+                Err(Error::InflateError(_)) => Err(Error::InflateError(error)),
+                other => other,
+            },
+            other => other,
+        }
+    }
+}
+
+/// checks if the data starts with a valid zlib header, ie deflate compression
+/// method and a header checksum that is a multiple of 31
+fn is_zlib_header(data: &[u8]) -> bool {
+    match data {
+        [cmf, flg, ..] => cmf & 0x0f == 8 && u16::from_be_bytes([*cmf, *flg]) % 31 == 0,
+        _ => false,
+    }
+}
+
+/// inflates all the concatenated zlib streams of the given data one after another
+/// until the input is exhausted, as long as the output doesn't exceed max_output
+fn inflate_zlib_streams(data: &[u8], max_output: usize) -> Result<Vec<u8>, Error> {
+    let mut output = vec![];
+    let mut remaining = data;
+    loop {
+        let mut decoder = ZlibDecoder::new(remaining).take((max_output - output.len()) as u64 + 1);
+        decoder
+            .read_to_end(&mut output)
+            .map_err(|e| Error::InflateError(e.to_string()))?;
+        if output.len() > max_output {
+            return Err(Error::DecompressionLimitExceeded);
+        }
+        let consumed = decoder.into_inner().total_in() as usize;
+        remaining = &remaining[consumed..];
+        if remaining.is_empty() || consumed == 0 {
+            return Ok(output);
+        }
+    }
+}
+
+/// reads the given decoder to the end as long as the output doesn't exceed max_output
+fn read_limited(decoder: impl Read, max_output: usize) -> Result<Vec<u8>, Error> {
+    let mut v = vec![];
+    decoder
+        .take(max_output as u64 + 1)
+        .read_to_end(&mut v)
+        .map_err(|e| Error::InflateError(e.to_string()))?;
+    if v.len() > max_output {
+        Err(Error::DecompressionLimitExceeded)
+    } else {
+        Ok(v)
     }
 }
 
@@ -147,7 +321,8 @@ pub enum ContentLanguage {
 
 /// # Rain Meta Document v1 Item (meta map)
 ///
-/// represents a rain meta data and configuration that can be cbor encoded or unpacked back to the meta types
+/// represents a rain meta data and configuration that can be cbor encoded or unpacked back to the meta types,
+/// prefer with_magic() over mutating the magic directly as it checks the payload is compatible with it
 #[derive(PartialEq, Debug, Clone)]
 pub struct RainMetaDocumentV1Item {
     pub payload: serde_bytes::ByteBuf,
@@ -173,32 +348,206 @@ impl TryFrom<RainMetaDocumentV1Item> for Vec<u8> {
     }
 }
 
+// io writer that feeds the written bytes into a keccak256 hasher
+struct KeccakWriter(Keccak256);
+
+impl Write for KeccakWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Builder of RainMetaDocumentV1Item, see RainMetaDocumentV1Item::builder()
+#[derive(Debug, Clone, Default)]
+pub struct RainMetaDocumentV1ItemBuilder {
+    payload: Vec<u8>,
+    magic: Option<KnownMagic>,
+    content_type: Option<ContentType>,
+    content_encoding: Option<ContentEncoding>,
+    content_language: Option<ContentLanguage>,
+    encode_payload: bool,
+}
+
+impl RainMetaDocumentV1ItemBuilder {
+    /// sets the payload
+    pub fn payload(mut self, payload: impl Into<Vec<u8>>) -> Self {
+        self.payload = payload.into();
+        self
+    }
+
+    /// sets the magic number, required
+    pub fn magic(mut self, magic: KnownMagic) -> Self {
+        self.magic = Some(magic);
+        self
+    }
+
+    /// sets the content type, defaults to ContentType::None
+    pub fn content_type(mut self, content_type: ContentType) -> Self {
+        self.content_type = Some(content_type);
+        self
+    }
+
+    /// sets the content encoding, defaults to ContentEncoding::None
+    pub fn content_encoding(mut self, content_encoding: ContentEncoding) -> Self {
+        self.content_encoding = Some(content_encoding);
+        self
+    }
+
+    /// sets the content language, defaults to ContentLanguage::None
+    pub fn content_language(mut self, content_language: ContentLanguage) -> Self {
+        self.content_language = Some(content_language);
+        self
+    }
+
+    /// encodes the payload with the content encoding on build(), use when the given
+    /// payload is not already encoded
+    pub fn encode_payload(mut self) -> Self {
+        self.encode_payload = true;
+        self
+    }
+
+    /// builds the item, errors with UnknownMagic if no magic number was set
+    pub fn build(self) -> Result<RainMetaDocumentV1Item, Error> {
+        let content_encoding = self.content_encoding.unwrap_or(ContentEncoding::None);
+        let payload = if self.encode_payload {
+            content_encoding.encode(&self.payload)
+        } else {
+            self.payload
+        };
+        Ok(RainMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from(payload),
+            magic: self.magic.ok_or(Error::UnknownMagic)?,
+            content_type: self.content_type.unwrap_or(ContentType::None),
+            content_encoding,
+            content_language: self.content_language.unwrap_or(ContentLanguage::None),
+        })
+    }
+}
+
+// constructors of correctly configured items for the known meta types
 impl RainMetaDocumentV1Item {
-    fn len(&self) -> usize {
-        let mut l = 2;
-        if !matches!(self.content_type, ContentType::None) {
-            l += 1;
-        }
-        if !matches!(self.content_encoding, ContentEncoding::None) {
-            l += 1;
-        }
-        if !matches!(self.content_language, ContentLanguage::None) {
-            l += 1;
+    /// DotrainV1 item of the given dotrain text
+    pub fn dotrain(text: &str) -> Self {
+        Self::text_item(text, KnownMagic::DotrainV1)
+    }
+
+    /// RainlangV1 item of the given rainlang text
+    pub fn rainlang(text: &str) -> Self {
+        Self::text_item(text, KnownMagic::RainlangV1)
+    }
+
+    /// RainlangSourceV1 item of the given rainlang source text
+    pub fn rainlang_source(text: &str) -> Self {
+        Self::text_item(text, KnownMagic::RainlangSourceV1)
+    }
+
+    /// AuthoringMetaV1 item of the validated and abi encoded given authoring meta, labelled
+    /// as cbor following the convention of the lib.rs example
+    pub fn authoring(meta: &AuthoringMeta) -> Result<Self, Error> {
+        Ok(Self::bytes_item(
+            meta.abi_encode_validate()?,
+            KnownMagic::AuthoringMetaV1,
+            ContentType::Cbor,
+        ))
+    }
+
+    /// OpMetaV1 item of the given op meta as json
+    pub fn op(meta: &types::op::v1::OpMeta) -> Result<Self, Error> {
+        Ok(Self::bytes_item(
+            serde_json::to_vec(meta)?,
+            KnownMagic::OpMetaV1,
+            ContentType::Json,
+        ))
+    }
+
+    /// SolidityAbiV2 item of the given abi as json
+    pub fn solidity_abi(meta: &types::solidity_abi::v2::SolidityAbiMeta) -> Result<Self, Error> {
+        Ok(Self::bytes_item(
+            serde_json::to_vec(meta)?,
+            KnownMagic::SolidityAbiV2,
+            ContentType::Json,
+        ))
+    }
+
+    /// InterpreterCallerMetaV1 item of the given meta as json
+    pub fn interpreter_caller(
+        meta: &types::interpreter_caller::v1::InterpreterCallerMeta,
+    ) -> Result<Self, Error> {
+        Ok(Self::bytes_item(
+            serde_json::to_vec(meta)?,
+            KnownMagic::InterpreterCallerMetaV1,
+            ContentType::Json,
+        ))
+    }
+
+    /// ExpressionDeployerV2BytecodeV1 item of the given deployed bytecode
+    pub fn expression_deployer_bytecode(bytecode: &[u8]) -> Self {
+        Self::bytes_item(
+            bytecode.to_vec(),
+            KnownMagic::ExpressionDeployerV2BytecodeV1,
+            ContentType::OctetStream,
+        )
+    }
+
+    fn text_item(text: &str, magic: KnownMagic) -> Self {
+        Self::bytes_item(text.as_bytes().to_vec(), magic, ContentType::OctetStream)
+    }
+
+    fn bytes_item(payload: Vec<u8>, magic: KnownMagic, content_type: ContentType) -> Self {
+        RainMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from(payload),
+            magic,
+            content_type,
+            content_encoding: ContentEncoding::None,
+            content_language: ContentLanguage::None,
         }
-        l
+    }
+}
+
+impl RainMetaDocumentV1Item {
+    /// starts building an item with all fields unset
+    pub fn builder() -> RainMetaDocumentV1ItemBuilder {
+        RainMetaDocumentV1ItemBuilder::default()
     }
 
-    /// method to hash(keccak256) the cbor encoded bytes of this instance
+    /// method to hash(keccak256) the cbor encoded bytes of this instance, the
+    /// encoded bytes are streamed into the hasher without being buffered
     pub fn hash(&self, as_rain_meta_document: bool) -> Result<[u8; 32], Error> {
+        let mut writer = KeccakWriter(Keccak256::new());
         if as_rain_meta_document {
-            Ok(keccak256(Self::cbor_encode_seq(
-                &vec![self.clone()],
-                KnownMagic::RainMetaDocumentV1,
-            )?)
-            .0)
-        } else {
-            Ok(keccak256(self.cbor_encode()?).0)
+            writer
+                .0
+                .update(KnownMagic::RainMetaDocumentV1.to_prefix_bytes());
         }
+        serde_cbor::to_writer(&mut writer, self)?;
+        Ok(writer.0.finalize().0)
+    }
+
+    /// method to hash(keccak256) the magic number prefix bytes followed by the unpacked
+    /// payload of this instance, unlike hash() this ignores the content type, encoding
+    /// and language, so the result stays the same when the payload is re-encoded
+    pub fn canonical_subject(&self) -> Result<[u8; 32], Error> {
+        let mut bytes = self.magic.to_prefix_bytes().to_vec();
+        bytes.extend(self.unpack()?);
+        Ok(keccak256(bytes).0)
+    }
+
+    /// the meta subject of this item as returned by IDescribedByMetaV1.describedByMetaV1()
+    /// of a contract described by it, i.e. the hash of the item cbor encoded as a rain
+    /// meta document, as that is the meta the contract emits and checks the hash of
+    pub fn describe_call_subject(&self) -> Result<[u8; 32], Error> {
+        self.hash(true)
+    }
+
+    // canonical subject of this instance, falls back to the hash of the cbor encoded
+    // bytes if the payload cannot be unpacked
+    fn subject_key(&self) -> Result<[u8; 32], Error> {
+        self.canonical_subject().or_else(|_| self.hash(false))
     }
 
     /// method to cbor encode
@@ -207,6 +556,105 @@ impl RainMetaDocumentV1Item {
         Ok(serde_cbor::to_writer(&mut bytes, &self).map(|_| bytes)?)
     }
 
+    /// deterministic cbor encoding of this item as per RFC 8949 section 4.2, payloads of
+    /// ContentType::Cbor that are not compressed and decode as a single cbor value are
+    /// re-encoded deterministically as well, so the same logical meta always has the
+    /// same bytes and hash regardless of the map insertion order it was built with,
+    /// other payloads such as abi encoded authoring metas are kept as they are
+    pub fn cbor_encode_canonical(&self) -> Result<Vec<u8>, Error> {
+        let mut item = self.clone();
+        if matches!(self.content_type, ContentType::Cbor)
+            && matches!(
+                self.content_encoding.canonicalize_encoding(),
+                ContentEncoding::None
+            )
+        {
+            if let Ok(payload) = serde_cbor::from_slice::<serde_cbor::Value>(&self.payload) {
+                let mut bytes = vec![];
+                write_cbor_canonical(&mut bytes, &payload);
+                item.payload = serde_bytes::ByteBuf::from(bytes);
+            }
+        }
+        let mut bytes = vec![];
+        write_cbor_canonical(&mut bytes, &serde_cbor::value::to_value(&item)?);
+        Ok(bytes)
+    }
+
+    /// cbor encodes this item wrapped in a cbor semantic tag of its magic number, for
+    /// interop with generic cbor tooling, cbor_decode() reads both tagged and untagged items
+    #[cfg(feature = "tagged-cbor")]
+    pub fn cbor_encode_tagged(&self) -> Result<Vec<u8>, Error> {
+        Ok(serde_cbor::to_vec(&serde_cbor::tags::Tagged::new(
+            Some(self.magic as u64),
+            self,
+        ))?)
+    }
+
+    /// json object of this item, i.e. magic, content type, encoding and language (null
+    /// when None) and the 0x prefixed hex payload
+    pub fn to_json_map(&self) -> serde_json::Map<String, serde_json::Value> {
+        let mut map = serde_json::Map::new();
+        map.insert("magic".to_string(), serde_json::json!(self.magic));
+        map.insert(
+            "content_type".to_string(),
+            serde_json::json!((self.content_type != ContentType::None).then_some(self.content_type)),
+        );
+        map.insert(
+            "content_encoding".to_string(),
+            serde_json::json!(
+                (self.content_encoding != ContentEncoding::None).then_some(self.content_encoding)
+            ),
+        );
+        map.insert(
+            "content_language".to_string(),
+            serde_json::json!(
+                (self.content_language != ContentLanguage::None).then_some(self.content_language)
+            ),
+        );
+        map.insert(
+            "payload".to_string(),
+            serde_json::json!(hex::encode_prefixed(&self.payload)),
+        );
+        map
+    }
+
+    /// json lines of the given items, one to_json_map() object per line
+    pub fn sequence_to_jsonl(items: &[RainMetaDocumentV1Item]) -> Result<String, Error> {
+        let mut jsonl = String::new();
+        for item in items {
+            jsonl.push_str(&serde_json::to_string(&item.to_json_map())?);
+            jsonl.push('\n');
+        }
+        Ok(jsonl)
+    }
+
+    /// method to cbor encode into alloy Bytes, e.g. for building calldata
+    pub fn to_bytes(&self) -> Result<Bytes, Error> {
+        Ok(Bytes::from(self.cbor_encode()?))
+    }
+
+    /// converts this instance into a data uri of its cbor encoded bytes,
+    /// i.e. data:application/cbor;base64,<...>
+    pub fn to_data_uri(&self) -> Result<String, Error> {
+        Ok(format!(
+            "{}{}",
+            CBOR_DATA_URI_PREFIX,
+            BASE64_STANDARD.encode(self.cbor_encode()?)
+        ))
+    }
+
+    /// builds an instance from a data uri produced by to_data_uri()
+    pub fn from_data_uri(uri: &str) -> Result<Self, Error> {
+        let encoded = uri
+            .strip_prefix(CBOR_DATA_URI_PREFIX)
+            .ok_or(Error::InvalidDataUri)?;
+        let mut metas = Self::cbor_decode(&BASE64_STANDARD.decode(encoded)?)?;
+        if metas.len() != 1 {
+            return Err(Error::InvalidDataUri);
+        }
+        Ok(metas.remove(0))
+    }
+
     /// builds a cbor sequence from given MetaMaps
     pub fn cbor_encode_seq(
         seq: &Vec<RainMetaDocumentV1Item>,
@@ -219,50 +667,53 @@ impl RainMetaDocumentV1Item {
         Ok(bytes)
     }
 
-    /// method to cbor decode from given bytes
+    /// same as cbor_encode_seq() but each item is wrapped in a cbor semantic tag of
+    /// its magic number, see cbor_encode_tagged()
+    #[cfg(feature = "tagged-cbor")]
+    pub fn cbor_encode_seq_tagged(
+        seq: &Vec<RainMetaDocumentV1Item>,
+        magic: KnownMagic,
+    ) -> Result<Vec<u8>, Error> {
+        let mut bytes: Vec<u8> = magic.to_prefix_bytes().to_vec();
+        for item in seq {
+            serde_cbor::to_writer(
+                &mut bytes,
+                &serde_cbor::tags::Tagged::new(Some(item.magic as u64), item),
+            )?;
+        }
+        Ok(bytes)
+    }
+
+    /// method to cbor decode from given bytes, each item can be either a definite or an
+    /// indefinite length cbor map and optionally wrapped in a cbor semantic tag, data
+    /// nested deeper than MAX_CBOR_NESTING_DEPTH errors with MaxDepthExceeded
     pub fn cbor_decode(data: &[u8]) -> Result<Vec<RainMetaDocumentV1Item>, Error> {
-        let mut track: Vec<usize> = vec![];
-        let mut metas: Vec<RainMetaDocumentV1Item> = vec![];
-        let mut is_rain_document_meta = false;
-        let mut len = data.len();
-        if data.starts_with(&KnownMagic::RainMetaDocumentV1.to_prefix_bytes()) {
-            is_rain_document_meta = true;
-            len -= 8;
-        }
-        let mut deserializer = match is_rain_document_meta {
-            true => serde_cbor::Deserializer::from_slice(&data[8..]),
-            false => serde_cbor::Deserializer::from_slice(data),
-        };
-        while match serde_cbor::Value::deserialize(&mut deserializer) {
-            Ok(cbor_map) => {
-                track.push(deserializer.byte_offset());
-                match serde_cbor::value::from_value(cbor_map) {
-                    Ok(meta) => metas.push(meta),
-                    Err(error) => Err(Error::SerdeCborError(error))?,
-                };
-                true
-            }
-            Err(error) => {
-                if error.is_eof() {
-                    if error.offset() == len as u64 {
-                        false
-                    } else {
-                        Err(Error::SerdeCborError(error))?
-                    }
-                } else {
-                    Err(Error::SerdeCborError(error))?
-                }
-            }
-        } {}
+        Ok(Self::cbor_decode_with_offsets(data)?
+            .into_iter()
+            .map(|(meta, _)| meta)
+            .collect())
+    }
 
-        if metas.is_empty()
-            || track.is_empty()
-            || track.len() != metas.len()
-            || len != track[track.len() - 1]
-        {
-            Err(Error::CorruptMeta)?
-        }
-        Ok(metas)
+    /// method to cbor decode from given bytes, each decoded item is paired with its
+    /// byte range in the given data (including the rain meta document prefix offset)
+    pub fn cbor_decode_with_offsets(
+        data: &[u8],
+    ) -> Result<Vec<(RainMetaDocumentV1Item, Range<usize>)>, Error> {
+        Self::cbor_decode_iter_with_offsets(data).collect()
+    }
+
+    /// lazily cbor decodes the items of the given bytes one at a time, corrupt data
+    /// is surfaced as the last item of the iterator the same way cbor_decode() does
+    pub fn cbor_decode_iter(
+        data: &[u8],
+    ) -> impl Iterator<Item = Result<RainMetaDocumentV1Item, Error>> + '_ {
+        Self::cbor_decode_iter_with_offsets(data).map(|item| item.map(|(meta, _)| meta))
+    }
+
+    fn cbor_decode_iter_with_offsets(
+        data: &[u8],
+    ) -> impl Iterator<Item = Result<(RainMetaDocumentV1Item, Range<usize>), Error>> + '_ {
+        cbor_decode_items(data)
     }
 
     // unpack the payload based on the configuration
@@ -270,9 +721,109 @@ impl RainMetaDocumentV1Item {
         ContentEncoding::decode(&self.content_encoding, self.payload.as_ref())
     }
 
-    // unpacks the payload to given meta type based on configuration
-    pub fn unpack_into<T: TryFrom<Self, Error = Error>>(self) -> Result<T, Error> {
+    /// one line summary of this item for logging, e.g.
+    /// `DotrainV1 [octet-stream/deflate/en] payload=36B unpacked=36B` for the deflated
+    /// `#main _ _: int-add(1 2) int-add(2 3)` dotrain,
+    /// the unpacked length is shown as `?` if the payload fails to unpack
+    pub fn summary(&self) -> String {
+        let unpacked = self
+            .unpack()
+            .map(|v| format!("{}B", v.len()))
+            .unwrap_or("?".to_string());
+        format!(
+            "{:?} [{}/{}/{}] payload={}B unpacked={}",
+            self.magic,
+            self.content_type,
+            self.content_encoding,
+            self.content_language,
+            self.payload.len(),
+            unpacked
+        )
+    }
+
+    /// checks that the unpacked payload has the expected shape of the magic number, e.g.
+    /// abi encoded AuthoringMetaV1 or utf8 text for DotrainV1, and is consistent with the
+    /// content type, except for the abi encoded authoring metas which are conventionally
+    /// labelled as cbor, errors with ContentTypeMismatch describing the issue
+    pub fn validate(&self) -> Result<(), Error> {
+        let payload = self.unpack()?;
+        let mismatch = |expected: &str, e: &dyn std::fmt::Display| {
+            Error::ContentTypeMismatch(format!(
+                "{} payload is not valid {}: {}",
+                self.magic, expected, e
+            ))
+        };
         match self.magic {
+            KnownMagic::DotrainV1 | KnownMagic::RainlangV1 | KnownMagic::RainlangSourceV1 => {
+                std::str::from_utf8(&payload).map_err(|e| mismatch("utf8 text", &e))?;
+            }
+            KnownMagic::AuthoringMetaV1 => {
+                AuthoringMeta::abi_decode(&payload)
+                    .map_err(|e| mismatch("abi encoded authoring meta v1", &e))?;
+            }
+            KnownMagic::AuthoringMetaV2 => {
+                types::authoring::v2::AuthoringMetaV2::abi_decode(&payload)
+                    .map_err(|e| mismatch("abi encoded authoring meta v2", &e))?;
+            }
+            KnownMagic::OpMetaV1 => {
+                serde_json::from_slice::<types::op::v1::OpMeta>(&payload)
+                    .map_err(|e| mismatch("op meta json", &e))?;
+            }
+            KnownMagic::SolidityAbiV2 => {
+                serde_json::from_slice::<types::solidity_abi::v2::SolidityAbiMeta>(&payload)
+                    .map_err(|e| mismatch("solidity abi json", &e))?;
+            }
+            KnownMagic::InterpreterCallerMetaV1 => {
+                serde_json::from_slice::<types::interpreter_caller::v1::InterpreterCallerMeta>(
+                    &payload,
+                )
+                .map_err(|e| mismatch("interpreter caller meta json", &e))?;
+            }
+            KnownMagic::AddressList => {
+                types::address_list::v1::AddressListV1::try_from(payload.as_slice())
+                    .map_err(|e| mismatch("address list", &e))?;
+            }
+            _ => {}
+        }
+        match self.content_type {
+            ContentType::Json => {
+                serde_json::from_slice::<serde_json::Value>(&payload)
+                    .map_err(|e| mismatch("json", &e))?;
+            }
+            // abi encoded authoring metas are conventionally labelled as cbor
+            ContentType::Cbor
+                if !matches!(
+                    self.magic,
+                    KnownMagic::AuthoringMetaV1 | KnownMagic::AuthoringMetaV2
+                ) =>
+            {
+                serde_cbor::from_slice::<serde_cbor::Value>(&payload)
+                    .map_err(|e| mismatch("cbor", &e))?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// changes the magic number of this item after checking the payload is compatible
+    /// with it, see validate(), e.g. utf8 text for DotrainV1
+    pub fn with_magic(mut self, magic: KnownMagic) -> Result<Self, Error> {
+        self.magic = magic;
+        self.validate()?;
+        Ok(self)
+    }
+
+    /// unpacks the payload and deserializes it as json into the given type, this is
+    /// mainly useful for items with ContentType::Json
+    pub fn unpack_json<T: serde::de::DeserializeOwned>(&self) -> Result<T, Error> {
+        Ok(serde_json::from_slice(&self.unpack()?)?)
+    }
+
+    // unpacks the payload to given meta type based on configuration, errors of the
+    // conversion are wrapped in UnpackFailed naming the magic of this item
+    pub fn unpack_into<T: TryFrom<Self, Error = Error>>(self) -> Result<T, Error> {
+        let magic = self.magic;
+        match magic {
             KnownMagic::OpMetaV1
             | KnownMagic::DotrainV1
             | KnownMagic::RainlangV1
@@ -282,108 +833,680 @@ impl RainMetaDocumentV1Item {
             | KnownMagic::AddressList
             | KnownMagic::InterpreterCallerMetaV1
             | KnownMagic::ExpressionDeployerV2BytecodeV1
-            | KnownMagic::RainlangSourceV1 => T::try_from(self),
+            | KnownMagic::RainlangSourceV1 => T::try_from(self).map_err(|e| Error::UnpackFailed {
+                magic,
+                reason: e.to_string(),
+            }),
             _ => Err(Error::UnsupportedMeta)?,
         }
     }
 }
 
-impl Serialize for RainMetaDocumentV1Item {
-    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-        let mut map = serializer.serialize_map(Some(self.len()))?;
-        map.serialize_entry(&0, &self.payload)?;
-        map.serialize_entry(&1, &(self.magic as u64))?;
-        match self.content_type {
-            ContentType::None => {}
-            content_type => map.serialize_entry(&2, &content_type)?,
-        }
-        match self.content_encoding {
-            ContentEncoding::None => {}
-            content_encoding => map.serialize_entry(&3, &content_encoding)?,
-        }
-        match self.content_language {
-            ContentLanguage::None => {}
-            content_language => map.serialize_entry(&4, &content_language)?,
-        }
-        map.end()
-    }
+/// Wrapper of a RainMetaDocumentV1Item whose equality and hash are based on the
+/// canonical subject, so differently encoded items of the same content are equal,
+/// e.g. for deduping in a HashSet, the subject is computed once on construction
+#[derive(Debug, Clone)]
+pub struct SubjectKeyed {
+    item: RainMetaDocumentV1Item,
+    subject: [u8; 32],
 }
 
-impl<'de> Deserialize<'de> for RainMetaDocumentV1Item {
-    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-        struct EncodedMap;
-        impl<'de> Visitor<'de> for EncodedMap {
-            type Value = RainMetaDocumentV1Item;
+impl SubjectKeyed {
+    /// wraps the given item, falls back to the hash of the cbor encoded item as the
+    /// subject if its payload cannot be unpacked
+    pub fn new(item: RainMetaDocumentV1Item) -> Result<Self, Error> {
+        let subject = item.subject_key()?;
+        Ok(SubjectKeyed { item, subject })
+    }
 
-            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-                formatter.write_str("rain meta cbor encoded bytes")
-            }
+    /// the wrapped item
+    pub fn item(&self) -> &RainMetaDocumentV1Item {
+        &self.item
+    }
 
-            fn visit_map<T: serde::de::MapAccess<'de>>(
-                self,
-                mut map: T,
-            ) -> Result<Self::Value, T::Error> {
-                let mut payload = None;
-                let mut magic: Option<u64> = None;
-                let mut content_type = None;
-                let mut content_encoding = None;
-                let mut content_language = None;
-                while match map.next_key() {
-                    Ok(Some(key)) => {
-                        match key {
-                            0 => payload = Some(map.next_value()?),
-                            1 => magic = Some(map.next_value()?),
-                            2 => content_type = Some(map.next_value()?),
-                            3 => content_encoding = Some(map.next_value()?),
-                            4 => content_language = Some(map.next_value()?),
-                            other => Err(serde::de::Error::custom(&format!(
-                                "found unexpected key in the map: {other}"
-                            )))?,
-                        };
-                        true
-                    }
-                    Ok(None) => false,
-                    Err(error) => Err(error)?,
-                } {}
-                let payload = payload.ok_or_else(|| serde::de::Error::missing_field("payload"))?;
-                let magic = match magic
-                    .ok_or_else(|| serde::de::Error::missing_field("magic number"))?
-                    .try_into()
-                {
-                    Ok(m) => m,
-                    _ => Err(serde::de::Error::custom("unknown magic number"))?,
-                };
-                let content_type = content_type.unwrap_or(ContentType::None);
-                let content_encoding = content_encoding.unwrap_or(ContentEncoding::None);
-                let content_language = content_language.unwrap_or(ContentLanguage::None);
+    /// the subject this wrapper is keyed by
+    pub fn subject(&self) -> [u8; 32] {
+        self.subject
+    }
 
-                Ok(RainMetaDocumentV1Item {
-                    payload,
-                    magic,
-                    content_type,
-                    content_encoding,
-                    content_language,
-                })
-            }
-        }
-        deserializer.deserialize_map(EncodedMap)
+    /// unwraps the item
+    pub fn into_inner(self) -> RainMetaDocumentV1Item {
+        self.item
     }
 }
 
-/// searches for a meta matching the given hash in given subgraphs urls
-pub async fn search(hash: &str, subgraphs: &Vec<String>) -> Result<query::MetaResponse, Error> {
-    let request_body = query::MetaQuery::build_query(query::meta_query::Variables {
-        hash: Some(hash.to_ascii_lowercase()),
-    });
-    let mut promises = vec![];
-
-    let client = Arc::new(Client::builder().build().map_err(Error::ReqwestError)?);
-    for url in subgraphs {
-        promises.push(Box::pin(query::process_meta_query(
-            client.clone(),
-            &request_body,
-            url,
-        )));
+impl PartialEq for SubjectKeyed {
+    fn eq(&self, other: &Self) -> bool {
+        self.subject == other.subject
+    }
+}
+
+impl Eq for SubjectKeyed {}
+
+impl std::hash::Hash for SubjectKeyed {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.subject.hash(state);
+    }
+}
+
+impl TryFrom<RainMetaDocumentV1Item> for SubjectKeyed {
+    type Error = Error;
+    fn try_from(value: RainMetaDocumentV1Item) -> Result<Self, Self::Error> {
+        SubjectKeyed::new(value)
+    }
+}
+
+impl Serialize for RainMetaDocumentV1Item {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_meta_map(
+            serializer,
+            &self.payload,
+            self.magic as u64,
+            self.content_type,
+            self.content_encoding,
+            self.content_language,
+        )
+    }
+}
+
+impl<'de> Deserialize<'de> for RainMetaDocumentV1Item {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        RawMetaDocumentV1Item::deserialize(deserializer)?
+            .try_into()
+            .map_err(|_| serde::de::Error::custom("unknown magic number"))
+    }
+}
+
+/// # Raw Rain Meta Document v1 Item
+///
+/// same as RainMetaDocumentV1Item but keeps the magic number as a raw u64, so items
+/// with magic numbers unknown to this version (e.g. produced by newer tooling) can
+/// still be decoded and re-encoded byte identically
+#[derive(PartialEq, Debug, Clone)]
+pub struct RawMetaDocumentV1Item {
+    pub payload: serde_bytes::ByteBuf,
+    pub magic: u64,
+    pub content_type: ContentType,
+    pub content_encoding: ContentEncoding,
+    pub content_language: ContentLanguage,
+}
+
+impl RawMetaDocumentV1Item {
+    /// the known magic of this item if any
+    pub fn known_magic(&self) -> Option<KnownMagic> {
+        KnownMagic::try_from(self.magic).ok()
+    }
+
+    /// method to cbor encode
+    pub fn cbor_encode(&self) -> Result<Vec<u8>, Error> {
+        let mut bytes: Vec<u8> = vec![];
+        Ok(serde_cbor::to_writer(&mut bytes, &self).map(|_| bytes)?)
+    }
+
+    /// builds a cbor sequence from given items
+    pub fn cbor_encode_seq(
+        seq: &[RawMetaDocumentV1Item],
+        magic: KnownMagic,
+    ) -> Result<Vec<u8>, Error> {
+        let mut bytes: Vec<u8> = magic.to_prefix_bytes().to_vec();
+        for item in seq {
+            serde_cbor::to_writer(&mut bytes, &item)?;
+        }
+        Ok(bytes)
+    }
+
+    /// method to cbor decode from given bytes, unlike RainMetaDocumentV1Item::cbor_decode()
+    /// this does not fail on unknown magic numbers
+    pub fn cbor_decode(data: &[u8]) -> Result<Vec<RawMetaDocumentV1Item>, Error> {
+        cbor_decode_items(data)
+            .map(|item| item.map(|(meta, _)| meta))
+            .collect()
+    }
+
+    /// unpacks the payload to given meta type, errors with UnsupportedMeta if the
+    /// magic number is unknown
+    pub fn unpack_into<T: TryFrom<RainMetaDocumentV1Item, Error = Error>>(
+        self,
+    ) -> Result<T, Error> {
+        RainMetaDocumentV1Item::try_from(self)
+            .map_err(|_| Error::UnsupportedMeta)?
+            .unpack_into()
+    }
+}
+
+impl From<RainMetaDocumentV1Item> for RawMetaDocumentV1Item {
+    fn from(value: RainMetaDocumentV1Item) -> Self {
+        RawMetaDocumentV1Item {
+            payload: value.payload,
+            magic: value.magic as u64,
+            content_type: value.content_type,
+            content_encoding: value.content_encoding,
+            content_language: value.content_language,
+        }
+    }
+}
+
+impl TryFrom<RawMetaDocumentV1Item> for RainMetaDocumentV1Item {
+    type Error = Error;
+    fn try_from(value: RawMetaDocumentV1Item) -> Result<Self, Self::Error> {
+        Ok(RainMetaDocumentV1Item {
+            magic: value.magic.try_into()?,
+            payload: value.payload,
+            content_type: value.content_type,
+            content_encoding: value.content_encoding,
+            content_language: value.content_language,
+        })
+    }
+}
+
+impl Serialize for RawMetaDocumentV1Item {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_meta_map(
+            serializer,
+            &self.payload,
+            self.magic,
+            self.content_type,
+            self.content_encoding,
+            self.content_language,
+        )
+    }
+}
+
+impl<'de> Deserialize<'de> for RawMetaDocumentV1Item {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct EncodedMap;
+        impl<'de> Visitor<'de> for EncodedMap {
+            type Value = RawMetaDocumentV1Item;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("rain meta cbor encoded bytes")
+            }
+
+            fn visit_map<T: serde::de::MapAccess<'de>>(
+                self,
+                mut map: T,
+            ) -> Result<Self::Value, T::Error> {
+                let mut payload = None;
+                let mut magic: Option<u64> = None;
+                let mut content_type = None;
+                let mut content_encoding = None;
+                let mut content_language = None;
+                while match map.next_key() {
+                    Ok(Some(key)) => {
+                        match key {
+                            0 => payload = Some(map.next_value()?),
+                            1 => magic = Some(map.next_value()?),
+                            2 => content_type = Some(map.next_value()?),
+                            3 => content_encoding = Some(map.next_value()?),
+                            4 => content_language = Some(map.next_value()?),
+                            other => Err(serde::de::Error::custom(&format!(
+                                "found unexpected key in the map: {other}"
+                            )))?,
+                        };
+                        true
+                    }
+                    Ok(None) => false,
+                    Err(error) => Err(error)?,
+                } {}
+                let payload = payload.ok_or_else(|| serde::de::Error::missing_field("payload"))?;
+                let magic = magic.ok_or_else(|| serde::de::Error::missing_field("magic number"))?;
+                let content_type = content_type.unwrap_or(ContentType::None);
+                let content_encoding = content_encoding.unwrap_or(ContentEncoding::None);
+                let content_language = content_language.unwrap_or(ContentLanguage::None);
+
+                Ok(RawMetaDocumentV1Item {
+                    payload,
+                    magic,
+                    content_type,
+                    content_encoding,
+                    content_language,
+                })
+            }
+        }
+        deserializer.deserialize_map(EncodedMap)
+    }
+}
+
+// serializes the fields of a meta item as a cbor map, fields with None values are omitted
+fn serialize_meta_map<S: Serializer>(
+    serializer: S,
+    payload: &serde_bytes::ByteBuf,
+    magic: u64,
+    content_type: ContentType,
+    content_encoding: ContentEncoding,
+    content_language: ContentLanguage,
+) -> Result<S::Ok, S::Error> {
+    let len = 2
+        + !matches!(content_type, ContentType::None) as usize
+        + !matches!(content_encoding, ContentEncoding::None) as usize
+        + !matches!(content_language, ContentLanguage::None) as usize;
+    let mut map = serializer.serialize_map(Some(len))?;
+    map.serialize_entry(&0, payload)?;
+    map.serialize_entry(&1, &magic)?;
+    match content_type {
+        ContentType::None => {}
+        content_type => map.serialize_entry(&2, &content_type)?,
+    }
+    match content_encoding {
+        ContentEncoding::None => {}
+        content_encoding => map.serialize_entry(&3, &content_encoding)?,
+    }
+    match content_language {
+        ContentLanguage::None => {}
+        content_language => map.serialize_entry(&4, &content_language)?,
+    }
+    map.end()
+}
+
+// marks an item as complete in the innermost open definite length container, closing
+// the containers that become complete as a result
+fn complete_cbor_item(open: &mut Vec<Option<u64>>) {
+    while let Some(Some(remaining)) = open.last_mut() {
+        *remaining -= 1;
+        if *remaining > 0 {
+            break;
+        }
+        open.pop();
+    }
+}
+
+// iteratively scans the given cbor data and errors with MaxDepthExceeded if arrays,
+// maps and tags are nested deeper than the given depth, malformed data is left for the
+// decoder to report
+fn check_cbor_depth(data: &[u8], max_depth: usize) -> Result<(), Error> {
+    // remaining items of each open container, None for indefinite length ones
+    let mut open: Vec<Option<u64>> = vec![];
+    let mut i = 0;
+    while i < data.len() {
+        let initial = data[i];
+        i += 1;
+        if initial == 0xff {
+            if let Some(None) = open.last() {
+                open.pop();
+                complete_cbor_item(&mut open);
+            }
+            continue;
+        }
+        let (major, info) = (initial >> 5, initial & 0x1f);
+        let arg = match info {
+            0..=23 => Some(info as u64),
+            24..=27 => {
+                let len = 1 << (info - 24);
+                let Some(bytes) = data.get(i..i + len) else {
+                    return Ok(());
+                };
+                i += len;
+                Some(bytes.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64))
+            }
+            31 => None,
+            _ => return Ok(()),
+        };
+        let remaining = match (major, arg) {
+            (2 | 3, Some(len)) => {
+                i = i.saturating_add(usize::try_from(len).unwrap_or(usize::MAX));
+                Some(0)
+            }
+            (4, len) => len,
+            (5, len) => len.map(|len| len.saturating_mul(2)),
+            (6, _) => Some(1),
+            // indefinite length strings are closed by a break like containers
+            (2 | 3, None) => None,
+            _ => Some(0),
+        };
+        if remaining == Some(0) {
+            complete_cbor_item(&mut open);
+        } else {
+            open.push(remaining);
+            if open.len() > max_depth {
+                return Err(Error::MaxDepthExceeded(max_depth));
+            }
+        }
+    }
+    Ok(())
+}
+
+// lazily cbor decodes the meta maps of the given rain meta document bytes into T
+// paired with their byte range in the given data
+fn cbor_decode_items<T: serde::de::DeserializeOwned>(
+    data: &[u8],
+) -> impl Iterator<Item = Result<(T, Range<usize>), Error>> + '_ {
+    let mut prefix_len = 0;
+    if data.starts_with(&KnownMagic::RainMetaDocumentV1.to_prefix_bytes()) {
+        prefix_len = 8;
+    }
+    let len = data.len() - prefix_len;
+    let mut depth_error = check_cbor_depth(&data[prefix_len..], MAX_CBOR_NESTING_DEPTH).err();
+    let mut deserializer = serde_cbor::Deserializer::from_slice(&data[prefix_len..]);
+    let mut end = 0;
+    let mut count = 0;
+    let mut done = false;
+    std::iter::from_fn(move || {
+        if done {
+            return None;
+        }
+        if let Some(error) = depth_error.take() {
+            done = true;
+            return Some(Err(error));
+        }
+        match serde_cbor::Value::deserialize(&mut deserializer) {
+            Ok(cbor_map) => {
+                let start = end;
+                end = deserializer.byte_offset();
+                count += 1;
+                // items may be wrapped in a semantic tag of their magic number
+                let cbor_map = match cbor_map {
+                    serde_cbor::Value::Tag(_, value) => *value,
+                    value => value,
+                };
+                match serde_cbor::value::from_value(cbor_map) {
+                    Ok(meta) => Some(Ok((meta, start + prefix_len..end + prefix_len))),
+                    Err(error) => {
+                        done = true;
+                        Some(Err(Error::SerdeCborError(error)))
+                    }
+                }
+            }
+            Err(error) => {
+                done = true;
+                if error.is_eof() && error.offset() == len as u64 {
+                    if count == 0 || end != len {
+                        Some(Err(Error::CorruptMeta))
+                    } else {
+                        None
+                    }
+                } else {
+                    Some(Err(Error::SerdeCborError(error)))
+                }
+            }
+        }
+    })
+}
+
+// writes the head of a cbor data item, i.e. its major type and the argument in the
+// shortest possible form
+fn write_cbor_head(out: &mut Vec<u8>, major: u8, arg: u64) {
+    let major = major << 5;
+    match arg {
+        0..=23 => out.push(major | arg as u8),
+        24..=0xff => out.extend([major | 24, arg as u8]),
+        0x100..=0xffff => {
+            out.push(major | 25);
+            out.extend((arg as u16).to_be_bytes());
+        }
+        0x10000..=0xffff_ffff => {
+            out.push(major | 26);
+            out.extend((arg as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(major | 27);
+            out.extend(arg.to_be_bytes());
+        }
+    }
+}
+
+// half precision bits of the given float if it can be represented as one without loss
+fn to_f16_bits(value: f32) -> Option<u16> {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exp = ((bits >> 23) & 0xff) as i32 - 127;
+    let mantissa = bits & 0x7f_ffff;
+    match exp {
+        128 if mantissa == 0 => Some(sign | 0x7c00),
+        -127 if mantissa == 0 => Some(sign),
+        -14..=15 if mantissa & 0x1fff == 0 => {
+            Some(sign | (((exp + 15) as u16) << 10) | (mantissa >> 13) as u16)
+        }
+        -24..=-15 => {
+            let shift = -(exp + 1) as u32;
+            let mantissa = mantissa | 0x80_0000;
+            (mantissa & ((1 << shift) - 1) == 0).then_some(sign | (mantissa >> shift) as u16)
+        }
+        _ => None,
+    }
+}
+
+// cbor encodes the given value deterministically as per RFC 8949 section 4.2, i.e.
+// shortest form integers and floats, definite lengths and map keys sorted by the
+// bytewise order of their encoded bytes
+fn write_cbor_canonical(out: &mut Vec<u8>, value: &serde_cbor::Value) {
+    use serde_cbor::Value;
+    match value {
+        Value::Null => out.push(0xf6),
+        Value::Bool(v) => out.push(if *v { 0xf5 } else { 0xf4 }),
+        Value::Integer(v) => {
+            let (major, arg) = if *v >= 0 {
+                (0, *v as u128)
+            } else {
+                (1, (-1 - *v) as u128)
+            };
+            match u64::try_from(arg) {
+                Ok(arg) => write_cbor_head(out, major, arg),
+                // bignums, tag 2 for positive and tag 3 for negative
+                Err(_) => {
+                    write_cbor_head(out, 6, 2 + major as u64);
+                    let bytes = arg.to_be_bytes();
+                    let start = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+                    write_cbor_head(out, 2, (bytes.len() - start) as u64);
+                    out.extend(&bytes[start..]);
+                }
+            }
+        }
+        Value::Float(v) => {
+            if v.is_nan() {
+                out.extend([0xf9, 0x7e, 0x00]);
+            } else if (*v as f32) as f64 == *v {
+                match to_f16_bits(*v as f32) {
+                    Some(half) => {
+                        out.push(0xf9);
+                        out.extend(half.to_be_bytes());
+                    }
+                    None => {
+                        out.push(0xfa);
+                        out.extend((*v as f32).to_be_bytes());
+                    }
+                }
+            } else {
+                out.push(0xfb);
+                out.extend(v.to_be_bytes());
+            }
+        }
+        Value::Bytes(v) => {
+            write_cbor_head(out, 2, v.len() as u64);
+            out.extend(v);
+        }
+        Value::Text(v) => {
+            write_cbor_head(out, 3, v.len() as u64);
+            out.extend(v.as_bytes());
+        }
+        Value::Array(v) => {
+            write_cbor_head(out, 4, v.len() as u64);
+            for item in v {
+                write_cbor_canonical(out, item);
+            }
+        }
+        Value::Map(v) => {
+            let mut entries = v
+                .iter()
+                .map(|(key, value)| {
+                    let mut key_bytes = vec![];
+                    write_cbor_canonical(&mut key_bytes, key);
+                    (key_bytes, value)
+                })
+                .collect::<Vec<_>>();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            write_cbor_head(out, 5, entries.len() as u64);
+            for (key_bytes, value) in entries {
+                out.extend(key_bytes);
+                write_cbor_canonical(out, value);
+            }
+        }
+        Value::Tag(tag, v) => {
+            write_cbor_head(out, 6, *tag);
+            write_cbor_canonical(out, v);
+        }
+        _ => {}
+    }
+}
+
+/// Options of subgraph searches, see search_with_opts()
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SearchOpts {
+    /// timeout of each request to a subgraph
+    pub timeout: Duration,
+    /// max number of retries of a failed request to a subgraph, only retryable
+    /// errors (see Error::is_retryable()) are retried
+    pub retries: u32,
+    /// delay before the first retry, doubles after each retry
+    pub backoff: Duration,
+}
+
+impl Default for SearchOpts {
+    fn default() -> Self {
+        SearchOpts {
+            timeout: Duration::from_secs(30),
+            retries: 2,
+            backoff: Duration::from_millis(500),
+        }
+    }
+}
+
+/// runs the given request with the timeout and retries of the given options
+async fn with_retries<T, F, Fut>(opts: &SearchOpts, request: F) -> Result<T, Error>
+where
+    F: Fn() -> Fut,
+    Fut: std::future::Future<Output = Result<T, Error>>,
+{
+    let mut backoff = opts.backoff;
+    let mut attempt = 0;
+    loop {
+        let result = match future::select(Box::pin(request()), Delay::new(opts.timeout)).await {
+            future::Either::Left((result, _)) => result,
+            future::Either::Right(_) => Err(Error::Timeout),
+        };
+        match result {
+            Err(e) if e.is_retryable() && attempt < opts.retries => {
+                attempt += 1;
+                Delay::new(backoff).await;
+                backoff *= 2;
+            }
+            other => return other,
+        }
+    }
+}
+
+/// searches for a meta matching the given hash in given subgraphs urls
+pub async fn search(hash: &str, subgraphs: &Vec<String>) -> Result<query::MetaResponse, Error> {
+    search_with_opts(hash, subgraphs, SearchOpts::default()).await
+}
+
+/// searches for a meta matching the given hash in given subgraphs urls, each subgraph
+/// request is wrapped in the timeout and retried based on the given options
+pub async fn search_with_opts(
+    hash: &str,
+    subgraphs: &Vec<String>,
+    opts: SearchOpts,
+) -> Result<query::MetaResponse, Error> {
+    Ok(search_traced_with_opts(hash, subgraphs, opts).await?.0)
+}
+
+/// searches for a meta matching the given hash in given subgraphs urls and returns
+/// it alongside the url of the subgraph that answered
+pub async fn search_traced(
+    hash: &str,
+    subgraphs: &Vec<String>,
+) -> Result<(query::MetaResponse, String), Error> {
+    search_traced_with_opts(hash, subgraphs, SearchOpts::default()).await
+}
+
+/// same as search_traced() with the given search options
+pub async fn search_traced_with_opts(
+    hash: &str,
+    subgraphs: &Vec<String>,
+    opts: SearchOpts,
+) -> Result<(query::MetaResponse, String), Error> {
+    search_meta(hash, subgraphs, opts, None).await
+}
+
+/// searches for a meta matching the given hash in given subgraphs urls, ignoring the
+/// subgraphs that are not indexed up to min_block yet or don't report their block
+pub async fn search_fresh(
+    hash: &str,
+    subgraphs: &Vec<String>,
+    min_block: u64,
+) -> Result<query::MetaResponse, Error> {
+    Ok(
+        search_meta(hash, subgraphs, SearchOpts::default(), Some(min_block))
+            .await?
+            .0,
+    )
+}
+
+async fn search_meta(
+    hash: &str,
+    subgraphs: &Vec<String>,
+    opts: SearchOpts,
+    min_block: Option<u64>,
+) -> Result<(query::MetaResponse, String), Error> {
+    let request_body = query::MetaQuery::build_query(query::meta_query::Variables {
+        hash: Some(hash.to_ascii_lowercase()),
+    });
+    let client = Arc::new(Client::builder().build().map_err(Error::ReqwestError)?);
+    let mut promises = vec![];
+    for url in subgraphs {
+        let client = &client;
+        let request_body = &request_body;
+        let opts = &opts;
+        promises.push(Box::pin(async move {
+            with_retries(opts, || {
+                query::process_meta_query(client.clone(), request_body, url)
+            })
+            .await
+            .and_then(|response| match min_block {
+                Some(min_block) if response.block_number.unwrap_or(0) < min_block => {
+                    Err(Error::NoRecordFound)
+                }
+                _ => Ok((response, url.clone())),
+            })
+        }));
+    }
+    let response_value = future::select_ok(promises.drain(..)).await?.0;
+    Ok(response_value)
+}
+
+/// searches for a meta matching the given hash in the given metaboard subgraphs urls, for
+/// endpoints that only serve the metaboard schema, returns the first meta of the fastest
+/// subgraph that has any
+pub async fn search_via_metaboard(
+    hash: &str,
+    urls: &Vec<String>,
+) -> Result<query::MetaResponse, Error> {
+    let hash: [u8; 32] = hex::decode(hash)
+        .ok()
+        .and_then(|v| v.try_into().ok())
+        .ok_or(Error::InvalidHash)?;
+    let mut promises = vec![];
+    for url in urls {
+        let Ok(url) = url::Url::parse(url) else {
+            continue;
+        };
+        promises.push(Box::pin(async move {
+            let client = MetaboardSubgraphClient::new(url);
+            match client.get_metabytes_by_hash(&hash).await {
+                Ok(metas) => metas
+                    .into_iter()
+                    .next()
+                    .map(|bytes| query::MetaResponse {
+                        bytes,
+                        block_number: None,
+                    })
+                    .ok_or(Error::NoRecordFound),
+                Err(MetaboardSubgraphClientError::Empty(_)) => Err(Error::NoRecordFound),
+                Err(e) => Err(e.into()),
+            }
+        }));
+    }
+    if promises.is_empty() {
+        return Err(Error::NoRecordFound);
     }
     let response_value = future::select_ok(promises.drain(..)).await?.0;
     Ok(response_value)
@@ -393,24 +1516,62 @@ pub async fn search(hash: &str, subgraphs: &Vec<String>) -> Result<query::MetaRe
 pub async fn search_deployer(
     hash: &str,
     subgraphs: &Vec<String>,
+) -> Result<DeployerResponse, Error> {
+    search_deployer_with_opts(hash, subgraphs, SearchOpts::default()).await
+}
+
+/// searches for an ExpressionDeployer matching the given hash in given subgraphs urls,
+/// each subgraph request is wrapped in the timeout and retried based on the given options
+pub async fn search_deployer_with_opts(
+    hash: &str,
+    subgraphs: &Vec<String>,
+    opts: SearchOpts,
 ) -> Result<DeployerResponse, Error> {
     let request_body = query::DeployerQuery::build_query(query::deployer_query::Variables {
         hash: Some(hash.to_ascii_lowercase()),
     });
-    let mut promises = vec![];
-
     let client = Arc::new(Client::builder().build().map_err(Error::ReqwestError)?);
+    let mut promises = vec![];
     for url in subgraphs {
-        promises.push(Box::pin(query::process_deployer_query(
-            client.clone(),
-            &request_body,
-            url,
-        )));
+        let client = &client;
+        let request_body = &request_body;
+        promises.push(Box::pin(with_retries(&opts, move || {
+            query::process_deployer_query(client.clone(), request_body, url)
+        })));
     }
     let response_value = future::select_ok(promises.drain(..)).await?.0;
     Ok(response_value)
 }
 
+/// fetches the metas of the given hash from a metaboard subgraph and cbor decodes each
+/// of them, fails with InvalidMetaDocument naming the first meta that doesn't decode
+pub async fn get_documents_by_hash(
+    client: &MetaboardSubgraphClient,
+    hash: &[u8; 32],
+) -> Result<Vec<Vec<RainMetaDocumentV1Item>>, Error> {
+    decode_documents(client.get_metabytes_by_hash(hash).await?)
+}
+
+/// fetches all the metas of the given subject from a metaboard subgraph and cbor decodes
+/// each of them, fails with InvalidMetaDocument naming the first meta that doesn't decode
+pub async fn get_documents_by_subject(
+    client: &MetaboardSubgraphClient,
+    subject: &BigInt,
+) -> Result<Vec<Vec<RainMetaDocumentV1Item>>, Error> {
+    decode_documents(client.get_all_metabytes_by_subject(subject).await?)
+}
+
+fn decode_documents(metas: Vec<Vec<u8>>) -> Result<Vec<Vec<RainMetaDocumentV1Item>>, Error> {
+    metas
+        .iter()
+        .enumerate()
+        .map(|(i, bytes)| {
+            RainMetaDocumentV1Item::cbor_decode(bytes)
+                .map_err(|e| Error::InvalidMetaDocument(i, Box::new(e)))
+        })
+        .collect()
+}
+
 /// checks if the given contract implements IDescribeByMetaV1 interface
 pub async fn implements_i_described_by_meta_v1(
     client: &ReadableClientHttp,
@@ -484,6 +1645,22 @@ impl NPE2Deployer {
         }
         false
     }
+
+    /// size of this record in bytes, i.e. sum of the lengths of all the byte fields plus
+    /// the abi encoded size of the authoring meta, used for cache budgeting
+    pub fn size_bytes(&self) -> usize {
+        self.meta_hash.len()
+            + self.meta_bytes.len()
+            + self.bytecode.len()
+            + self.parser.len()
+            + self.store.len()
+            + self.interpreter.len()
+            + self
+                .authoring_meta
+                .as_ref()
+                .and_then(|v| v.abi_encode().ok())
+                .map_or(0, |v| v.len())
+    }
 }
 
 /// # Meta Storage(CAS)
@@ -561,16 +1738,150 @@ pub struct Store {
     dotrain_cache: HashMap<String, Vec<u8>>,
     deployer_cache: HashMap<Vec<u8>, NPE2Deployer>,
     deployer_hash_map: HashMap<Vec<u8>, Vec<u8>>,
+    /// insertion time of the metas that were fetched from subgraphs, metas
+    /// that are not in this map are local
+    #[serde(default)]
+    fetched_at: HashMap<Vec<u8>, SystemTime>,
+    /// max total bytes of the cached metas, least recently used metas are
+    /// evicted once exceeded, unbounded if None
+    #[serde(default)]
+    max_bytes: Option<usize>,
+    #[serde(skip)]
+    recency: Recency,
+    #[serde(skip)]
+    hit_miss: HitMiss,
+    #[serde(skip)]
+    evicted: Evicted,
+}
+
+/// last use ticks of the cached metas, used for lru eviction
+#[derive(Debug, Default)]
+struct Recency(Mutex<(u64, HashMap<Vec<u8>, u64>)>);
+
+impl Recency {
+    fn lock(&self) -> MutexGuard<'_, (u64, HashMap<Vec<u8>, u64>)> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn touch(&self, hash: &[u8]) {
+        let mut guard = self.lock();
+        guard.0 += 1;
+        let tick = guard.0;
+        guard.1.insert(hash.to_vec(), tick);
+    }
+
+    fn last_used(&self, hash: &[u8]) -> u64 {
+        self.lock().1.get(hash).copied().unwrap_or(0)
+    }
+
+    fn remove(&self, hash: &[u8]) {
+        self.lock().1.remove(hash);
+    }
+
+    fn clear(&self) {
+        self.lock().1.clear();
+    }
+}
+
+impl Clone for Recency {
+    fn clone(&self) -> Self {
+        Recency(Mutex::new(self.lock().clone()))
+    }
+}
+
+// recency is bookkeeping and not part of a store's content
+impl PartialEq for Recency {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+/// cache hit and miss counters of the meta lookups, see Store::hit_miss()
+#[derive(Debug, Default)]
+struct HitMiss {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl HitMiss {
+    fn hit(&self) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn miss(&self) {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl Clone for HitMiss {
+    fn clone(&self) -> Self {
+        HitMiss {
+            hits: AtomicU64::new(self.hits.load(Ordering::Relaxed)),
+            misses: AtomicU64::new(self.misses.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+// counters are bookkeeping and not part of a store's content
+impl PartialEq for HitMiss {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+/// hashes of the metas evicted by the last cache insert, see Store::last_evicted()
+#[derive(Debug, Default, Clone)]
+struct Evicted(Vec<Vec<u8>>);
+
+// evicted hashes are bookkeeping and not part of a store's content
+impl PartialEq for Evicted {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+/// Policy of Store::merge_with() for dotrain uris that exist in both stores
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergePolicy {
+    /// keeps the hash of the store being merged into
+    #[default]
+    KeepExisting,
+    /// takes the hash of the other (presumably newer) store
+    PreferOther,
+}
+
+/// env var that overrides the default subgraphs of a Store, comma-separated urls
+pub const SUBGRAPHS_ENV_VAR: &str = "RAIN_METABOARD_SUBGRAPHS";
+
+// subgraphs of the given value of RAIN_METABOARD_SUBGRAPHS env var, falls back to NPE2
+// subgraphs if it is not set or empty
+fn subgraphs_from_env(value: Option<&str>) -> Vec<String> {
+    match value {
+        Some(urls) if !urls.trim().is_empty() => urls
+            .split(',')
+            .map(|url| url.trim())
+            .filter(|url| !url.is_empty())
+            .map(|url| url.to_string())
+            .collect(),
+        _ => KnownSubgraphs::NPE2.map(|url| url.to_string()).to_vec(),
+    }
 }
 
 impl Default for Store {
+    /// uses the urls of RAIN_METABOARD_SUBGRAPHS env var if set, falls back to NPE2 subgraphs
     fn default() -> Self {
+        let subgraphs = subgraphs_from_env(std::env::var(SUBGRAPHS_ENV_VAR).ok().as_deref());
         Store {
             cache: HashMap::new(),
             dotrain_cache: HashMap::new(),
             deployer_cache: HashMap::new(),
-            subgraphs: KnownSubgraphs::NPE2.map(|url| url.to_string()).to_vec(),
+            subgraphs,
             deployer_hash_map: HashMap::new(),
+            fetched_at: HashMap::new(),
+            max_bytes: None,
+            recency: Recency::default(),
+            hit_miss: HitMiss::default(),
+            evicted: Evicted::default(),
         }
     }
 }
@@ -585,6 +1896,21 @@ impl Store {
             dotrain_cache: HashMap::new(),
             deployer_cache: HashMap::new(),
             deployer_hash_map: HashMap::new(),
+            fetched_at: HashMap::new(),
+            max_bytes: None,
+            recency: Recency::default(),
+            hit_miss: HitMiss::default(),
+            evicted: Evicted::default(),
+        }
+    }
+
+    /// creates a new empty instance that keeps the total bytes of the cached metas
+    /// under max_bytes by evicting the least recently used ones, metas that are
+    /// referenced by dotrain or deployer records are never evicted
+    pub fn with_capacity(max_bytes: usize) -> Store {
+        Store {
+            max_bytes: Some(max_bytes),
+            ..Store::new()
         }
     }
 
@@ -596,6 +1922,49 @@ impl Store {
         deployer_cache: &HashMap<Vec<u8>, NPE2Deployer>,
         dotrain_cache: &HashMap<String, Vec<u8>>,
         include_rain_subgraphs: bool,
+    ) -> Store {
+        let valid_items = cache
+            .iter()
+            .filter(|(hash, bytes)| keccak256(bytes).0 == hash.as_slice());
+        Store::create_from_valid(
+            subgraphs,
+            valid_items,
+            deployer_cache,
+            dotrain_cache,
+            include_rain_subgraphs,
+        )
+    }
+
+    /// same as create() but validates (keccak256 checks) the cache items in parallel
+    /// across threads before inserting them, which is much faster for large caches
+    pub fn create_parallel(
+        subgraphs: &Vec<String>,
+        cache: &HashMap<Vec<u8>, Vec<u8>>,
+        deployer_cache: &HashMap<Vec<u8>, NPE2Deployer>,
+        dotrain_cache: &HashMap<String, Vec<u8>>,
+        include_rain_subgraphs: bool,
+    ) -> Store {
+        let valid_items: Vec<(&Vec<u8>, &Vec<u8>)> = cache
+            .par_iter()
+            .filter(|(hash, bytes)| keccak256(bytes).0 == hash.as_slice())
+            .collect();
+        Store::create_from_valid(
+            subgraphs,
+            valid_items,
+            deployer_cache,
+            dotrain_cache,
+            include_rain_subgraphs,
+        )
+    }
+
+    // shared setup of create() and create_parallel(), the given cache items must already
+    // be validated, the deployer and dotrain records are checked here
+    fn create_from_valid<'a>(
+        subgraphs: &Vec<String>,
+        valid_items: impl IntoIterator<Item = (&'a Vec<u8>, &'a Vec<u8>)>,
+        deployer_cache: &HashMap<Vec<u8>, NPE2Deployer>,
+        dotrain_cache: &HashMap<String, Vec<u8>>,
+        include_rain_subgraphs: bool,
     ) -> Store {
         let mut store;
         if include_rain_subgraphs {
@@ -604,8 +1973,11 @@ impl Store {
             store = Store::new();
         }
         store.add_subgraphs(subgraphs);
-        for (hash, bytes) in cache {
-            store.update_with(hash, bytes);
+        for (hash, bytes) in valid_items {
+            if !store.cache.contains_key(hash) {
+                store.store_content(bytes);
+                store.cache.insert(hash.clone(), bytes.clone());
+            }
         }
         for (hash, deployer) in deployer_cache {
             store.set_deployer(hash, deployer, None);
@@ -632,14 +2004,76 @@ impl Store {
         }
     }
 
+    /// replaces all the subgraph endpoints with the given ones, duplicates are dropped
+    pub fn set_subgraphs(&mut self, subgraphs: Vec<String>) {
+        self.subgraphs.clear();
+        self.add_subgraphs(&subgraphs);
+    }
+
     /// getter method for the whole meta cache
     pub fn cache(&self) -> &HashMap<Vec<u8>, Vec<u8>> {
         &self.cache
     }
 
+    /// number of the cached metas
+    pub fn meta_count(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// total byte length of the cached metas
+    pub fn cache_bytes(&self) -> usize {
+        self.cache.values().map(|bytes| bytes.len()).sum()
+    }
+
+    /// checks if a meta of the given hash is cached
+    pub fn contains_meta(&self, hash: &[u8]) -> bool {
+        self.cache.contains_key(hash)
+    }
+
+    /// checks if the given dotrain uri is mapped to a meta
+    pub fn contains_dotrain(&self, uri: &str) -> bool {
+        self.dotrain_cache.contains_key(uri)
+    }
+
     /// get the corresponding meta bytes of the given hash if it exists
     pub fn get_meta(&self, hash: &[u8]) -> Option<&Vec<u8>> {
-        self.cache.get(hash)
+        let Some(bytes) = self.touch_meta(hash) else {
+            self.hit_miss.miss();
+            return None;
+        };
+        self.hit_miss.hit();
+        Some(bytes)
+    }
+
+    // gets the cached meta bytes of the given hash marking it as recently used, unlike
+    // get_meta() this is for internal lookups so it doesn't count as a hit or miss
+    fn touch_meta(&self, hash: &[u8]) -> Option<&Vec<u8>> {
+        let bytes = self.cache.get(hash)?;
+        self.recency.touch(hash);
+        Some(bytes)
+    }
+
+    /// number of the cache hits and misses of the meta lookups so far, i.e. get_meta()
+    /// and update_check() calls
+    pub fn hit_miss(&self) -> (u64, u64) {
+        (
+            self.hit_miss.hits.load(Ordering::Relaxed),
+            self.hit_miss.misses.load(Ordering::Relaxed),
+        )
+    }
+
+    /// hashes of the metas that were evicted to fit max_bytes by the last insert into
+    /// the meta cache, least recently used first, empty if that insert evicted nothing
+    pub fn last_evicted(&self) -> &[Vec<u8>] {
+        &self.evicted.0
+    }
+
+    /// same as get_meta() but first checks that the cached bytes cbor decode cleanly,
+    /// so a corrupt cache entry surfaces as an error
+    pub fn get_meta_validated(&self, hash: &[u8]) -> Option<Result<&Vec<u8>, Error>> {
+        self.cache
+            .get(hash)
+            .map(|bytes| RainMetaDocumentV1Item::cbor_decode(bytes).map(|_| bytes))
     }
 
     /// getter method for the whole authoring meta cache
@@ -647,6 +2081,16 @@ impl Store {
         &self.deployer_cache
     }
 
+    /// iterates over the cached deployer hashes and their NPE2Deployer records
+    pub fn deployers(&self) -> impl Iterator<Item = (&Vec<u8>, &NPE2Deployer)> {
+        self.deployer_cache.iter()
+    }
+
+    /// number of the cached NPE2Deployer records
+    pub fn deployer_count(&self) -> usize {
+        self.deployer_cache.len()
+    }
+
     /// get the corresponding DeployerNPRecord of the given deployer hash if it exists
     pub fn get_deployer(&self, hash: &[u8]) -> Option<&NPE2Deployer> {
         if self.deployer_cache.contains_key(hash) {
@@ -660,10 +2104,21 @@ impl Store {
 
     /// searches for DeployerNPRecord in the subgraphs given the deployer hash
     pub async fn search_deployer(&mut self, hash: &[u8]) -> Option<&NPE2Deployer> {
+        self.try_search_deployer(hash).await.ok().flatten()
+    }
+
+    /// same as search_deployer() but the subgraph errors are propagated, a deployer
+    /// that is not found in any of the subgraphs results in Ok(None)
+    pub async fn try_search_deployer(
+        &mut self,
+        hash: &[u8],
+    ) -> Result<Option<&NPE2Deployer>, Error> {
         match search_deployer(&hex::encode_prefixed(hash), &self.subgraphs).await {
             Ok(res) => {
                 self.cache
                     .insert(res.meta_hash.clone(), res.meta_bytes.clone());
+                self.fetched_at
+                    .insert(res.meta_hash.clone(), SystemTime::now());
                 let authoring_meta = res.get_authoring_meta();
                 self.deployer_cache.insert(
                     res.bytecode_meta_hash.clone(),
@@ -678,23 +2133,78 @@ impl Store {
                     },
                 );
                 self.deployer_hash_map.insert(res.tx_hash, res.meta_hash);
-                self.deployer_cache.get(hash)
+                Ok(self.deployer_cache.get(hash))
             }
-            Err(_e) => None,
+            Err(Error::NoRecordFound) => Ok(None),
+            Err(e) => Err(e),
         }
     }
 
     /// if the NPE2Deployer record already is cached it returns it immediately else
     /// searches for NPE2Deployer in the subgraphs given the deployer hash
     pub async fn search_deployer_check(&mut self, hash: &[u8]) -> Option<&NPE2Deployer> {
+        self.try_search_deployer_check(hash).await.ok().flatten()
+    }
+
+    /// same as search_deployer_check() but the subgraph errors are propagated, see
+    /// try_search_deployer()
+    pub async fn try_search_deployer_check(
+        &mut self,
+        hash: &[u8],
+    ) -> Result<Option<&NPE2Deployer>, Error> {
         if self.deployer_cache.contains_key(hash) {
-            self.get_deployer(hash)
+            Ok(self.get_deployer(hash))
         } else if self.deployer_hash_map.contains_key(hash) {
             let b_hash = self.deployer_hash_map.get(hash).unwrap();
-            self.get_deployer(b_hash)
+            Ok(self.get_deployer(b_hash))
         } else {
-            self.search_deployer(hash).await
+            self.try_search_deployer(hash).await
+        }
+    }
+
+    /// searches for multiple NPE2Deployer records in the subgraphs concurrently given their
+    /// deployer hashes and caches those that are found, at most DEFAULT_UPDATE_CONCURRENCY
+    /// searches at a time, returns a map of each given hash to whether or not a record was
+    /// found for it, or the subgraph error if the search failed
+    pub async fn search_deployers(
+        &mut self,
+        hashes: &[Vec<u8>],
+    ) -> HashMap<Vec<u8>, Result<bool, Error>> {
+        self.search_deployers_with_limit(hashes, DEFAULT_UPDATE_CONCURRENCY)
+            .await
+    }
+
+    /// same as search_deployers() with the given max number of concurrent searches
+    pub async fn search_deployers_with_limit(
+        &mut self,
+        hashes: &[Vec<u8>],
+        limit: usize,
+    ) -> HashMap<Vec<u8>, Result<bool, Error>> {
+        let subgraphs = &self.subgraphs;
+        let responses: Vec<_> = stream::iter(hashes)
+            .map(|hash| async move {
+                (
+                    hash.clone(),
+                    search_deployer(&hex::encode_prefixed(hash), subgraphs).await,
+                )
+            })
+            .buffer_unordered(limit.max(1))
+            .collect()
+            .await;
+
+        let mut result = HashMap::new();
+        for (hash, response) in responses {
+            let found = match response {
+                Ok(res) => {
+                    self.set_deployer_from_query_response(res);
+                    Ok(true)
+                }
+                Err(Error::NoRecordFound) => Ok(false),
+                Err(e) => Err(e),
+            };
+            result.insert(hash, found);
         }
+        result
     }
 
     /// sets deployer record from the deployer query response
@@ -714,6 +2224,8 @@ impl Store {
             interpreter: deployer_query_response.interpreter,
             authoring_meta,
         };
+        self.fetched_at
+            .insert(deployer_query_response.meta_hash.clone(), SystemTime::now());
         self.cache
             .insert(deployer_query_response.meta_hash, result.meta_bytes.clone());
         self.deployer_hash_map
@@ -764,7 +2276,28 @@ impl Store {
 
     /// get the corresponding meta bytes of the given dotrain uri if it exists
     pub fn get_dotrain_meta(&self, uri: &str) -> Option<&Vec<u8>> {
-        self.get_meta(self.dotrain_cache.get(uri)?)
+        self.touch_meta(self.dotrain_cache.get(uri)?)
+    }
+
+    /// get the cbor decoded meta items of the given dotrain uri if it exists
+    pub fn get_dotrain_meta_items(
+        &self,
+        uri: &str,
+    ) -> Option<Result<Vec<RainMetaDocumentV1Item>, Error>> {
+        self.get_dotrain_meta(uri)
+            .map(|bytes| RainMetaDocumentV1Item::cbor_decode(bytes))
+    }
+
+    /// get the dotrain text of the given dotrain uri if it exists, the stored meta is
+    /// unpacked so the text is the same regardless of its content encoding
+    pub fn get_dotrain_text(&self, uri: &str) -> Option<Result<String, Error>> {
+        self.get_dotrain_meta_items(uri).map(|items| {
+            items?
+                .into_iter()
+                .find(|item| item.magic == KnownMagic::DotrainV1)
+                .ok_or(Error::UnsupportedMeta)?
+                .try_into()
+        })
     }
 
     /// deletes a dotrain record given a uri
@@ -776,12 +2309,47 @@ impl Store {
         };
     }
 
+    /// removes a meta from the cache given its hash, also removes the dotrain uris
+    /// and deployer hash map records that point to it
+    pub fn remove_meta(&mut self, hash: &[u8]) -> Option<Vec<u8>> {
+        let bytes = self.cache.remove(hash)?;
+        self.fetched_at.remove(hash);
+        self.recency.remove(hash);
+        self.dotrain_cache.retain(|_, h| h != hash);
+        self.deployer_hash_map.retain(|_, h| h != hash);
+        Some(bytes)
+    }
+
+    /// empties all the caches of this instance, keeps the subgraphs
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.dotrain_cache.clear();
+        self.deployer_cache.clear();
+        self.deployer_hash_map.clear();
+        self.fetched_at.clear();
+        self.recency.clear();
+    }
+
+    /// total bytes of the cached metas and the configured max bytes, if any
+    pub fn capacity_used(&self) -> (usize, Option<usize>) {
+        (self.cache_bytes(), self.max_bytes)
+    }
+
     /// lazilly merges another Store to the current one, avoids duplicates
     pub fn merge(&mut self, other: &Store) {
+        self.merge_with(other, MergePolicy::KeepExisting)
+    }
+
+    /// same as merge() but resolves dotrain uris that are mapped to different hashes
+    /// in the two stores based on the given policy
+    pub fn merge_with(&mut self, other: &Store, policy: MergePolicy) {
         self.add_subgraphs(&other.subgraphs);
         for (hash, bytes) in &other.cache {
             if !self.cache.contains_key(hash) {
                 self.cache.insert(hash.clone(), bytes.clone());
+                if let Some(time) = other.fetched_at.get(hash) {
+                    self.fetched_at.insert(hash.clone(), *time);
+                }
             }
         }
         for (hash, deployer) in &other.deployer_cache {
@@ -793,7 +2361,7 @@ impl Store {
             self.deployer_hash_map.insert(hash.clone(), tx_hash.clone());
         }
         for (uri, hash) in &other.dotrain_cache {
-            if !self.dotrain_cache.contains_key(uri) {
+            if policy == MergePolicy::PreferOther || !self.dotrain_cache.contains_key(uri) {
                 self.dotrain_cache.insert(uri.clone(), hash.clone());
             }
         }
@@ -802,24 +2370,140 @@ impl Store {
     /// updates the meta cache by searching through all subgraphs for the given hash
     /// returns the reference to the meta bytes in the cache if it was found
     pub async fn update(&mut self, hash: &[u8]) -> Option<&Vec<u8>> {
-        if let Ok(meta) = search(&hex::encode_prefixed(hash), &self.subgraphs).await {
-            self.store_content(&meta.bytes);
-            self.cache.insert(hash.to_vec(), meta.bytes);
-            return self.get_meta(hash);
-        } else {
-            None
+        self.try_update(hash).await.ok().flatten()
+    }
+
+    /// same as update() but the subgraph errors are propagated so a failing subgraph
+    /// can be told apart from a meta that is not found, the latter results in Ok(None)
+    pub async fn try_update(&mut self, hash: &[u8]) -> Result<Option<&Vec<u8>>, Error> {
+        match search(&hex::encode_prefixed(hash), &self.subgraphs).await {
+            Ok(meta) => Ok(self.set_fetched_meta(hash, meta.bytes)),
+            Err(Error::NoRecordFound) => Ok(None),
+            Err(e) => Err(e),
         }
     }
 
+    /// checks all the cached metas against the given metaboard subgraph and returns the
+    /// hashes that the subgraph doesn't have, i.e. the metas that are not published yet,
+    /// at most DEFAULT_UPDATE_CONCURRENCY queries at a time
+    pub async fn diff_against_subgraph(
+        &self,
+        client: &MetaboardSubgraphClient,
+    ) -> Result<Vec<[u8; 32]>, Error> {
+        self.diff_against_subgraph_with_limit(client, DEFAULT_UPDATE_CONCURRENCY)
+            .await
+    }
+
+    /// same as diff_against_subgraph() with the given max number of concurrent queries
+    pub async fn diff_against_subgraph_with_limit(
+        &self,
+        client: &MetaboardSubgraphClient,
+        limit: usize,
+    ) -> Result<Vec<[u8; 32]>, Error> {
+        let hashes: Vec<[u8; 32]> = self
+            .cache
+            .keys()
+            .filter_map(|hash| <[u8; 32]>::try_from(hash.as_slice()).ok())
+            .collect();
+        let mut responses = stream::iter(hashes)
+            .map(|hash| async move { (hash, client.get_metabytes_by_hash(&hash).await) })
+            .buffer_unordered(limit.max(1));
+
+        let mut unpublished = vec![];
+        while let Some((hash, response)) = responses.next().await {
+            match response {
+                Ok(_) => {}
+                Err(MetaboardSubgraphClientError::Empty(_)) => unpublished.push(hash),
+                Err(e) => return Err(e.into()),
+            }
+        }
+        unpublished.sort();
+        Ok(unpublished)
+    }
+
+    /// updates the meta cache by searching through all subgraphs for the given hashes
+    /// concurrently, at most DEFAULT_UPDATE_CONCURRENCY searches at a time, returns a
+    /// map of each given hash to whether or not it was found
+    pub async fn update_many(&mut self, hashes: &[Vec<u8>]) -> HashMap<Vec<u8>, bool> {
+        self.update_many_with_limit(hashes, DEFAULT_UPDATE_CONCURRENCY)
+            .await
+    }
+
+    /// same as update_many() with the given max number of concurrent searches
+    pub async fn update_many_with_limit(
+        &mut self,
+        hashes: &[Vec<u8>],
+        limit: usize,
+    ) -> HashMap<Vec<u8>, bool> {
+        let subgraphs = self.subgraphs.clone();
+        let fetch = |hash: &Vec<u8>| {
+            let subgraphs = &subgraphs;
+            let hash = hash.clone();
+            async move {
+                let result = search(&hex::encode_prefixed(&hash), subgraphs).await;
+                (hash, result)
+            }
+        };
+        let mut pending = hashes.iter();
+        let mut in_flight = FuturesUnordered::new();
+        for hash in pending.by_ref().take(limit.max(1)) {
+            in_flight.push(fetch(hash));
+        }
+
+        let mut result = HashMap::new();
+        while let Some((hash, response)) = in_flight.next().await {
+            if let Some(next_hash) = pending.next() {
+                in_flight.push(fetch(next_hash));
+            }
+            let found = match response {
+                Ok(meta) => self.set_fetched_meta(&hash, meta.bytes).is_some(),
+                Err(_) => false,
+            };
+            result.insert(hash, found);
+        }
+        result
+    }
+
+    /// stores the given meta bytes that were fetched from subgraphs for the given hash
+    fn set_fetched_meta(&mut self, hash: &[u8], bytes: Vec<u8>) -> Option<&Vec<u8>> {
+        self.store_content(&bytes);
+        self.cache.insert(hash.to_vec(), bytes);
+        self.fetched_at.insert(hash.to_vec(), SystemTime::now());
+        self.evict(hash);
+        self.touch_meta(hash)
+    }
+
     /// first checks if the meta is stored, if not will perform update()
     pub async fn update_check(&mut self, hash: &[u8]) -> Option<&Vec<u8>> {
         if !self.cache.contains_key(hash) {
+            self.hit_miss.miss();
             self.update(hash).await
         } else {
             return self.get_meta(hash);
         }
     }
 
+    /// removes the metas that were fetched from subgraphs and are older than the given age,
+    /// locally added metas are kept, returns the number of removed metas
+    pub fn expire_older_than(&mut self, age: Duration) -> usize {
+        let now = SystemTime::now();
+        let expired: Vec<Vec<u8>> = self
+            .fetched_at
+            .iter()
+            .filter(|(_, time)| now.duration_since(**time).unwrap_or_default() > age)
+            .map(|(hash, _)| hash.clone())
+            .collect();
+        let mut count = 0;
+        for hash in &expired {
+            self.fetched_at.remove(hash);
+            if self.remove_meta(hash).is_some() {
+                count += 1;
+            }
+        }
+        self.shrink();
+        count
+    }
+
     /// updates the meta cache by the given hash and meta bytes, checks the hash to bytes
     /// validity returns the reference to the bytes if the updated meta bytes contained any
     pub fn update_with(&mut self, hash: &[u8], bytes: &[u8]) -> Option<&Vec<u8>> {
@@ -827,13 +2511,34 @@ impl Store {
             if keccak256(bytes).0 == hash {
                 self.store_content(bytes);
                 self.cache.insert(hash.to_vec(), bytes.to_vec());
-                return self.cache.get(hash);
+                self.evict(hash);
+                return self.touch_meta(hash);
             } else {
                 None
             }
         } else {
-            return self.get_meta(hash);
+            return self.touch_meta(hash);
+        }
+    }
+
+    /// updates the meta cache by the given hash and meta bytes pairs, see update_with(),
+    /// returns the number of pairs that passed the hash check and were newly stored, i.e.
+    /// already cached hashes are skipped and not counted
+    pub fn update_with_many<I: IntoIterator<Item = (Vec<u8>, Vec<u8>)>>(
+        &mut self,
+        entries: I,
+    ) -> usize {
+        let mut count = 0;
+        for (hash, bytes) in entries {
+            if self.cache.contains_key(&hash) || keccak256(&bytes).0 != hash.as_slice() {
+                continue;
+            }
+            self.store_content(&bytes);
+            self.cache.insert(hash.clone(), bytes);
+            self.evict(&hash);
+            count += 1;
         }
+        count
     }
 
     /// stores (or updates in case the URI already exists) the given dotrain text as meta into the store cache
@@ -846,14 +2551,22 @@ impl Store {
         uri: &str,
         keep_old: bool,
     ) -> Result<(Vec<u8>, Vec<u8>), Error> {
-        let bytes = RainMetaDocumentV1Item {
-            payload: serde_bytes::ByteBuf::from(text.as_bytes()),
-            magic: KnownMagic::DotrainV1,
-            content_type: ContentType::OctetStream,
-            content_encoding: ContentEncoding::None,
-            content_language: ContentLanguage::None,
-        }
-        .cbor_encode()?;
+        self.set_dotrain_encoded(text, uri, keep_old, ContentEncoding::None)
+    }
+
+    /// same as set_dotrain() but the dotrain text is encoded with the given content
+    /// encoding before being cbor encoded, e.g. to keep large dotrain files compressed
+    pub fn set_dotrain_encoded(
+        &mut self,
+        text: &str,
+        uri: &str,
+        keep_old: bool,
+        encoding: ContentEncoding,
+    ) -> Result<(Vec<u8>, Vec<u8>), Error> {
+        let mut item = RainMetaDocumentV1Item::dotrain(text);
+        item.payload = serde_bytes::ByteBuf::from(encoding.encode(text.as_bytes()));
+        item.content_encoding = encoding;
+        let bytes = item.cbor_encode()?;
         let new_hash = keccak256(&bytes).0.to_vec();
         if let Some(h) = self.dotrain_cache.get(uri) {
             let old_hash = h.clone();
@@ -875,6 +2588,124 @@ impl Store {
         }
     }
 
+    /// writes the meta cache into a tar archive with one `<hexhash>.cbor` entry per
+    /// cached meta, entries are sorted by their hash so the archive is reproducible
+    pub fn export_tar(&self, w: impl Write) -> Result<(), Error> {
+        let mut builder = tar::Builder::new(w);
+        let mut hashes: Vec<&Vec<u8>> = self.cache.keys().collect();
+        hashes.sort();
+        for hash in hashes {
+            let bytes = &self.cache[hash];
+            let mut header = tar::Header::new_gnu();
+            header.set_size(bytes.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(
+                &mut header,
+                format!("{}.cbor", hex::encode(hash)),
+                bytes.as_slice(),
+            )?;
+        }
+        builder.finish()?;
+        Ok(())
+    }
+
+    /// reads metas from a tar archive produced by export_tar() into the meta cache,
+    /// each entry goes through update_with() so only those whose hash matches their
+    /// bytes are stored and entries not named as `<hexhash>.cbor` are skipped
+    pub fn import_tar(&mut self, r: impl Read) -> Result<(), Error> {
+        let mut archive = tar::Archive::new(r);
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let hash = match entry
+                .path()?
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_suffix(".cbor"))
+                .map(hex::decode)
+            {
+                Some(Ok(hash)) => hash,
+                _ => continue,
+            };
+            let mut bytes = vec![];
+            entry.read_to_end(&mut bytes)?;
+            self.update_with(&hash, &bytes);
+        }
+        self.shrink();
+        Ok(())
+    }
+
+    /// releases the excess capacity of the internal maps and vectors, this is called
+    /// at the end of bulk operations such as import_tar() and expire_older_than()
+    pub fn shrink(&mut self) {
+        self.subgraphs.shrink_to_fit();
+        self.cache.shrink_to_fit();
+        self.dotrain_cache.shrink_to_fit();
+        self.deployer_cache.shrink_to_fit();
+        self.deployer_hash_map.shrink_to_fit();
+        self.fetched_at.shrink_to_fit();
+    }
+
+    /// cbor encodes the whole store and writes it to the given path
+    pub fn save_to_path(&self, path: &Path) -> Result<(), Error> {
+        Ok(std::fs::write(path, serde_cbor::to_vec(self)?)?)
+    }
+
+    /// reads a store saved by save_to_path() from the given path, cache items whose
+    /// hash doesn't match their bytes are dropped
+    pub fn load_from_path(path: &Path) -> Result<Store, Error> {
+        let mut store: Store = serde_cbor::from_slice(&std::fs::read(path)?)?;
+        store
+            .cache
+            .retain(|hash, bytes| keccak256(bytes).0 == hash.as_slice());
+        let cache = &store.cache;
+        store
+            .dotrain_cache
+            .retain(|_, hash| cache.contains_key(hash));
+        store.fetched_at.retain(|hash, _| cache.contains_key(hash));
+        Ok(store)
+    }
+
+    /// evicts the least recently used metas until the total cached bytes fit in
+    /// max_bytes, skips the pinned metas (referenced by dotrain or deployer records)
+    /// and the given hash, the evicted hashes are kept for last_evicted()
+    fn evict(&mut self, keep: &[u8]) {
+        self.evicted.0.clear();
+        let max_bytes = match self.max_bytes {
+            Some(v) => v,
+            None => return,
+        };
+        let mut total = self.capacity_used().0;
+        if total <= max_bytes {
+            return;
+        }
+        let mut candidates: Vec<(u64, Vec<u8>)> = {
+            let pinned: HashSet<&Vec<u8>> = self
+                .dotrain_cache
+                .values()
+                .chain(self.deployer_hash_map.values())
+                .chain(self.deployer_cache.values().map(|d| &d.meta_hash))
+                .collect();
+            self.cache
+                .keys()
+                .filter(|hash| hash.as_slice() != keep && !pinned.contains(hash))
+                .map(|hash| (self.recency.last_used(hash), hash.clone()))
+                .collect()
+        };
+        candidates.sort();
+        for (_, hash) in candidates {
+            if total <= max_bytes {
+                break;
+            }
+            if let Some(bytes) = self.cache.remove(&hash) {
+                total -= bytes.len();
+                self.fetched_at.remove(&hash);
+                self.recency.remove(&hash);
+                self.evicted.0.push(hash);
+            }
+        }
+    }
+
     /// decodes each meta and stores the inner meta items into the cache
     /// if any of the inner items is an authoring meta, stores it in authoring meta cache as well
     /// returns the reference to the authoring bytes if the meta bytes contained any
@@ -883,8 +2714,9 @@ impl Store {
             if bytes.starts_with(&KnownMagic::RainMetaDocumentV1.to_prefix_bytes()) {
                 for meta_map in &meta_maps {
                     if let Ok(encoded_bytes) = meta_map.cbor_encode() {
-                        self.cache
-                            .insert(keccak256(&encoded_bytes).0.to_vec(), encoded_bytes);
+                        let hash = keccak256(&encoded_bytes).0.to_vec();
+                        self.recency.touch(&hash);
+                        self.cache.insert(hash, encoded_bytes);
                     }
                 }
             }
@@ -892,6 +2724,90 @@ impl Store {
     }
 }
 
+/// Thread safe handle of a Store that can be cloned and shared across threads and tasks,
+/// read methods only take a read lock and subgraph requests are made without holding
+/// any lock so concurrent reads are not blocked while fetching
+#[derive(Clone, Debug, Default)]
+pub struct SharedStore(Arc<RwLock<Store>>);
+
+impl From<Store> for SharedStore {
+    fn from(value: Store) -> Self {
+        SharedStore(Arc::new(RwLock::new(value)))
+    }
+}
+
+impl SharedStore {
+    /// creates a new instance from the given Store
+    pub fn new(store: Store) -> Self {
+        store.into()
+    }
+
+    /// takes a read lock of the underlying Store
+    pub fn read(&self) -> RwLockReadGuard<'_, Store> {
+        self.0.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// takes a write lock of the underlying Store
+    pub fn write(&self) -> RwLockWriteGuard<'_, Store> {
+        self.0.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// clones the current state of the underlying Store
+    pub fn snapshot(&self) -> Store {
+        self.read().clone()
+    }
+
+    /// all subgraph endpoints of the underlying Store
+    pub fn subgraphs(&self) -> Vec<String> {
+        self.read().subgraphs.clone()
+    }
+
+    /// get the corresponding meta bytes of the given hash if it exists
+    pub fn get_meta(&self, hash: &[u8]) -> Option<Vec<u8>> {
+        self.read().get_meta(hash).cloned()
+    }
+
+    /// get the corresponding NPE2Deployer of the given deployer hash if it exists
+    pub fn get_deployer(&self, hash: &[u8]) -> Option<NPE2Deployer> {
+        self.read().get_deployer(hash).cloned()
+    }
+
+    /// get the corresponding meta bytes of the given dotrain uri if it exists
+    pub fn get_dotrain_meta(&self, uri: &str) -> Option<Vec<u8>> {
+        self.read().get_dotrain_meta(uri).cloned()
+    }
+
+    /// updates the meta cache by the given hash and meta bytes, see Store::update_with()
+    pub fn update_with(&self, hash: &[u8], bytes: &[u8]) -> Option<Vec<u8>> {
+        self.write().update_with(hash, bytes).cloned()
+    }
+
+    /// updates the meta cache by the given hash and meta bytes pairs, see
+    /// Store::update_with_many()
+    pub fn update_with_many<I: IntoIterator<Item = (Vec<u8>, Vec<u8>)>>(
+        &self,
+        entries: I,
+    ) -> usize {
+        self.write().update_with_many(entries)
+    }
+
+    /// updates the meta cache by searching through all subgraphs for the given hash,
+    /// the search happens outside of the lock
+    pub async fn update(&self, hash: &[u8]) -> Option<Vec<u8>> {
+        let subgraphs = self.subgraphs();
+        let meta = search(&hex::encode_prefixed(hash), &subgraphs).await.ok()?;
+        self.write().set_fetched_meta(hash, meta.bytes).cloned()
+    }
+
+    /// first checks if the meta is stored, if not will perform update()
+    pub async fn update_check(&self, hash: &[u8]) -> Option<Vec<u8>> {
+        match self.get_meta(hash) {
+            Some(bytes) => Some(bytes),
+            None => self.update(hash).await,
+        }
+    }
+}
+
 /// converts string to bytes32
 pub fn str_to_bytes32(text: &str) -> Result<[u8; 32], Error> {
     let bytes: &[u8] = text.as_bytes();
@@ -918,9 +2834,13 @@ mod tests {
         *, bytes32_to_str,
         magic::KnownMagic,
         str_to_bytes32,
-        types::{authoring::v1::AuthoringMeta, dotrain::v1::DotrainMeta},
+        types::{
+            authoring::v1::AuthoringMeta, dotrain::v1::DotrainMeta,
+            solidity_abi::v2::SolidityAbiMeta,
+        },
         ContentEncoding, ContentLanguage, ContentType, Error, RainMetaDocumentV1Item,
     };
+    use strum::IntoEnumIterator;
     use alloy_ethers_typecast::{
         request_shim::{AlloyTransactionRequest, TransactionRequestShim},
         rpc::{eip2718::TypedTransaction, BlockNumber, Request, Response},
@@ -1016,40 +2936,194 @@ mod tests {
         Ok(())
     }
 
-    /// Roundtrip test for a dotrain meta
-    /// original content -> pack -> MetaMap -> cbor encode -> cbor decode -> MetaMap -> unpack -> original content,
     #[test]
-    fn dotrain_meta_roundtrip() -> Result<(), Error> {
+    fn test_summary() {
         let dotrain_content = "#main _ _: int-add(1 2) int-add(2 3)";
-        let dotrain_content_bytes = dotrain_content.as_bytes().to_vec();
-
         let content_encoding = ContentEncoding::Deflate;
-        let deflated_payload = content_encoding.encode(&dotrain_content_bytes);
-
-        let meta_map = RainMetaDocumentV1Item {
+        let deflated_payload = content_encoding.encode(dotrain_content.as_bytes());
+        let mut meta_map = RainMetaDocumentV1Item {
             payload: serde_bytes::ByteBuf::from(deflated_payload.clone()),
             magic: KnownMagic::DotrainV1,
             content_type: ContentType::OctetStream,
             content_encoding,
             content_language: ContentLanguage::En,
         };
-        let cbor_encoded = meta_map.cbor_encode()?;
-
-        // cbor map with 5 keys
-        assert_eq!(cbor_encoded[0], 0xa5);
-        // key 0
-        assert_eq!(cbor_encoded[1], 0x00);
-        // major type 2 (bytes) length 36
-        assert_eq!(cbor_encoded[2], 0b010_11000);
-        assert_eq!(cbor_encoded[3], 0b001_00100);
-        // assert_eq!(cbor_encoded[4], 0b000_00000);
-        // payload
-        assert_eq!(cbor_encoded[4..40], deflated_payload);
-        // key 1
-        assert_eq!(cbor_encoded[40], 0x01);
-        // major type 0 (unsigned integer) value 27
-        assert_eq!(cbor_encoded[41], 0b000_11011);
-        // magic number
+        assert_eq!(
+            meta_map.summary(),
+            format!(
+                "DotrainV1 [octet-stream/deflate/en] payload={}B unpacked={}B",
+                deflated_payload.len(),
+                dotrain_content.len()
+            )
+        );
+        // the doc example of summary()
+        assert_eq!(
+            meta_map.summary(),
+            "DotrainV1 [octet-stream/deflate/en] payload=36B unpacked=36B"
+        );
+
+        meta_map.payload = serde_bytes::ByteBuf::from(vec![0xff; 4]);
+        assert_eq!(
+            meta_map.summary(),
+            "DotrainV1 [octet-stream/deflate/en] payload=4B unpacked=?"
+        );
+    }
+
+    #[test]
+    fn test_cbor_decode_tagged_and_untagged() -> Result<(), Error> {
+        let meta_map = RainMetaDocumentV1Item::dotrain("#main _ _: int-add(1 2)");
+        let untagged = meta_map.cbor_encode()?;
+        assert_eq!(
+            RainMetaDocumentV1Item::cbor_decode(&untagged)?,
+            vec![meta_map.clone()]
+        );
+
+        // major type 6 with an 8 byte tag of the magic number
+        let mut tagged = vec![0xdb];
+        tagged.extend(KnownMagic::DotrainV1.to_prefix_bytes());
+        tagged.extend(&untagged);
+        assert_eq!(
+            RainMetaDocumentV1Item::cbor_decode(&tagged)?,
+            vec![meta_map.clone()]
+        );
+
+        // a sequence mixing both forms
+        let mut seq = KnownMagic::RainMetaDocumentV1.to_prefix_bytes().to_vec();
+        seq.extend(&tagged);
+        seq.extend(&untagged);
+        assert_eq!(
+            RainMetaDocumentV1Item::cbor_decode(&seq)?,
+            vec![meta_map.clone(), meta_map]
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "tagged-cbor")]
+    #[test]
+    fn test_cbor_encode_tagged_roundtrip() -> Result<(), Error> {
+        let meta_map = RainMetaDocumentV1Item::dotrain("#main _ _: int-add(1 2)");
+        let tagged = meta_map.cbor_encode_tagged()?;
+        assert_eq!(tagged[0], 0xdb);
+        assert_eq!(&tagged[1..9], KnownMagic::DotrainV1.to_prefix_bytes());
+        assert_eq!(&tagged[9..], meta_map.cbor_encode()?);
+        assert_eq!(
+            RainMetaDocumentV1Item::cbor_decode(&tagged)?,
+            vec![meta_map.clone()]
+        );
+
+        let seq = RainMetaDocumentV1Item::cbor_encode_seq_tagged(
+            &vec![meta_map.clone(), meta_map.clone()],
+            KnownMagic::RainMetaDocumentV1,
+        )?;
+        assert_eq!(
+            RainMetaDocumentV1Item::cbor_decode(&seq)?,
+            vec![meta_map.clone(), meta_map]
+        );
+
+        Ok(())
+    }
+
+    /// encoding test vectors of the meta items of each known magic, with none, deflate,
+    /// gzip, brotli and zstd content encodings of a dotrain payload
+    #[test]
+    fn test_parity_vectors() -> Result<(), Error> {
+        #[derive(serde::Deserialize)]
+        struct Input {
+            payload: String,
+            magic: KnownMagic,
+            content_type: Option<ContentType>,
+            content_encoding: Option<ContentEncoding>,
+            content_language: Option<ContentLanguage>,
+        }
+        #[derive(serde::Deserialize)]
+        struct Vector {
+            name: String,
+            input: Input,
+            expected_cbor_hex: String,
+            expected_subject: String,
+            #[serde(default)]
+            expected_unpacked: Option<String>,
+        }
+
+        let vectors: Vec<Vector> = serde_json::from_str(include_str!(
+            "../../test-vectors/rain_meta_document_v1_items.json"
+        ))?;
+        for vector in vectors {
+            let item = RainMetaDocumentV1Item {
+                payload: serde_bytes::ByteBuf::from(hex::decode(&vector.input.payload).unwrap()),
+                magic: vector.input.magic,
+                content_type: vector.input.content_type.unwrap_or(ContentType::None),
+                content_encoding: vector
+                    .input
+                    .content_encoding
+                    .unwrap_or(ContentEncoding::None),
+                content_language: vector
+                    .input
+                    .content_language
+                    .unwrap_or(ContentLanguage::None),
+            };
+            let cbor = item.cbor_encode()?;
+            assert_eq!(
+                hex::encode_prefixed(&cbor),
+                vector.expected_cbor_hex,
+                "{}",
+                vector.name
+            );
+            assert_eq!(
+                hex::encode_prefixed(item.hash(false)?),
+                vector.expected_subject,
+                "{}",
+                vector.name
+            );
+            if let Some(expected_unpacked) = &vector.expected_unpacked {
+                assert_eq!(
+                    &hex::encode_prefixed(item.unpack()?),
+                    expected_unpacked,
+                    "{}",
+                    vector.name
+                );
+            }
+            assert_eq!(RainMetaDocumentV1Item::cbor_decode(&cbor)?, vec![item]);
+        }
+
+        Ok(())
+    }
+
+    /// Roundtrip test for a dotrain meta
+    /// original content -> pack -> MetaMap -> cbor encode -> cbor decode -> MetaMap -> unpack -> original content,
+    #[test]
+    fn dotrain_meta_roundtrip() -> Result<(), Error> {
+        let dotrain_content = "#main _ _: int-add(1 2) int-add(2 3)";
+        let dotrain_content_bytes = dotrain_content.as_bytes().to_vec();
+
+        let content_encoding = ContentEncoding::Deflate;
+        let deflated_payload = content_encoding.encode(&dotrain_content_bytes);
+
+        let meta_map = RainMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from(deflated_payload.clone()),
+            magic: KnownMagic::DotrainV1,
+            content_type: ContentType::OctetStream,
+            content_encoding,
+            content_language: ContentLanguage::En,
+        };
+        let cbor_encoded = meta_map.cbor_encode()?;
+
+        // cbor map with 5 keys
+        assert_eq!(cbor_encoded[0], 0xa5);
+        // key 0
+        assert_eq!(cbor_encoded[1], 0x00);
+        // major type 2 (bytes) length 36
+        assert_eq!(cbor_encoded[2], 0b010_11000);
+        assert_eq!(cbor_encoded[3], 0b001_00100);
+        // assert_eq!(cbor_encoded[4], 0b000_00000);
+        // payload
+        assert_eq!(cbor_encoded[4..40], deflated_payload);
+        // key 1
+        assert_eq!(cbor_encoded[40], 0x01);
+        // major type 0 (unsigned integer) value 27
+        assert_eq!(cbor_encoded[41], 0b000_11011);
+        // magic number
         assert_eq!(
             &cbor_encoded[42..50],
             KnownMagic::DotrainV1.to_prefix_bytes()
@@ -1089,6 +3163,249 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_streaming_hash() -> Result<(), Error> {
+        let item = RainMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from(
+                (0..4 * 1024 * 1024).map(|i| i as u8).collect::<Vec<u8>>(),
+            ),
+            magic: KnownMagic::ExpressionDeployerV2BytecodeV1,
+            content_type: ContentType::OctetStream,
+            content_encoding: ContentEncoding::None,
+            content_language: ContentLanguage::None,
+        };
+        assert_eq!(item.hash(false)?, keccak256(item.cbor_encode()?).0);
+        assert_eq!(
+            item.hash(true)?,
+            keccak256(RainMetaDocumentV1Item::cbor_encode_seq(
+                &vec![item.clone()],
+                KnownMagic::RainMetaDocumentV1
+            )?)
+            .0
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_item_builder() -> Result<(), Error> {
+        let dotrain_content = "#main _ _: int-add(1 2) int-add(2 3)";
+
+        // same item as the dotrain_meta_roundtrip fixture
+        let expected = RainMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from(
+                ContentEncoding::Deflate.encode(dotrain_content.as_bytes()),
+            ),
+            magic: KnownMagic::DotrainV1,
+            content_type: ContentType::OctetStream,
+            content_encoding: ContentEncoding::Deflate,
+            content_language: ContentLanguage::En,
+        };
+        let built = RainMetaDocumentV1Item::builder()
+            .payload(dotrain_content)
+            .magic(KnownMagic::DotrainV1)
+            .content_type(ContentType::OctetStream)
+            .content_encoding(ContentEncoding::Deflate)
+            .content_language(ContentLanguage::En)
+            .encode_payload()
+            .build()?;
+        assert_eq!(built, expected);
+        assert_eq!(built.unpack_into::<DotrainMeta>()?, dotrain_content);
+
+        // without encode_payload() the payload is taken as is
+        let built = RainMetaDocumentV1Item::builder()
+            .payload(expected.payload.to_vec())
+            .magic(KnownMagic::DotrainV1)
+            .content_type(ContentType::OctetStream)
+            .content_encoding(ContentEncoding::Deflate)
+            .content_language(ContentLanguage::En)
+            .build()?;
+        assert_eq!(built, expected);
+
+        // unset fields default to None
+        let built = RainMetaDocumentV1Item::builder()
+            .payload("_: 1;")
+            .magic(KnownMagic::RainlangV1)
+            .build()?;
+        assert_eq!(built.content_type, ContentType::None);
+        assert_eq!(built.content_encoding, ContentEncoding::None);
+        assert_eq!(built.content_language, ContentLanguage::None);
+
+        assert!(matches!(
+            RainMetaDocumentV1Item::builder().build(),
+            Err(Error::UnknownMagic)
+        ));
+
+        Ok(())
+    }
+
+    /// Roundtrip test for a gzip encoded dotrain meta
+    /// original content -> pack -> MetaMap -> cbor encode -> cbor decode -> MetaMap -> unpack -> original content,
+    #[test]
+    fn dotrain_meta_gzip_roundtrip() -> Result<(), Error> {
+        let dotrain_content = "#main _ _: int-add(1 2) int-add(2 3)";
+        let dotrain_content_bytes = dotrain_content.as_bytes().to_vec();
+
+        let content_encoding = ContentEncoding::Gzip;
+        let gzipped_payload = content_encoding.encode(&dotrain_content_bytes);
+        // gzip magic bytes
+        assert_eq!(gzipped_payload[..2], [0x1f, 0x8b]);
+
+        let meta_map = RainMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from(gzipped_payload.clone()),
+            magic: KnownMagic::DotrainV1,
+            content_type: ContentType::OctetStream,
+            content_encoding,
+            content_language: ContentLanguage::En,
+        };
+        let cbor_encoded = meta_map.cbor_encode()?;
+
+        // key 3 followed by text string gzip length 4, then key 4 and en
+        let index = cbor_encoded.len() - 10;
+        assert_eq!(cbor_encoded[index], 0x03);
+        assert_eq!(cbor_encoded[index + 1], 0b011_00100);
+        assert_eq!(&cbor_encoded[index + 2..index + 6], "gzip".as_bytes());
+        assert_eq!(cbor_encoded[index + 6], 0x04);
+
+        // decode the data back to MetaMap
+        let mut cbor_decoded = RainMetaDocumentV1Item::cbor_decode(&cbor_encoded)?;
+        assert_eq!(cbor_decoded.len(), 1);
+        assert_eq!(cbor_decoded[0], meta_map);
+
+        // unpack the payload into DotrainMeta, should handle gunzip of the payload internally
+        let unpacked_payload: DotrainMeta = cbor_decoded.pop().unwrap().unpack_into()?;
+        assert_eq!(unpacked_payload, dotrain_content);
+
+        // zlib data labeled as gzip should still decode through the fallback
+        let zlib_payload = ContentEncoding::Deflate.encode(&dotrain_content_bytes);
+        assert_eq!(
+            ContentEncoding::Gzip.decode(&zlib_payload)?,
+            dotrain_content_bytes
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_with_limit() -> Result<(), Error> {
+        // 16 MiB of zeros compresses down to a few KiB
+        let data = vec![0u8; 16 * 1024 * 1024];
+        for content_encoding in [ContentEncoding::Deflate, ContentEncoding::Gzip] {
+            let encoded = content_encoding.encode(&data);
+            assert!(encoded.len() < 64 * 1024);
+
+            assert!(matches!(
+                content_encoding.decode_with_limit(&encoded, 1024),
+                Err(Error::DecompressionLimitExceeded)
+            ));
+            assert!(matches!(
+                content_encoding.decode_with_limit(&encoded, data.len() - 1),
+                Err(Error::DecompressionLimitExceeded)
+            ));
+            assert_eq!(
+                content_encoding.decode_with_limit(&encoded, data.len())?,
+                data
+            );
+            assert_eq!(content_encoding.decode(&encoded)?, data);
+
+            // same through unpack
+            let meta_map = RainMetaDocumentV1Item {
+                payload: serde_bytes::ByteBuf::from(encoded),
+                magic: KnownMagic::DotrainV1,
+                content_type: ContentType::OctetStream,
+                content_encoding,
+                content_language: ContentLanguage::None,
+            };
+            assert_eq!(meta_map.unpack()?, data);
+        }
+
+        // uncompressed data is not limited
+        assert_eq!(ContentEncoding::None.decode_with_limit(&data, 1)?, data);
+
+        // corrupt data still results in inflate error
+        assert!(matches!(
+            ContentEncoding::Deflate.decode_with_limit(&[0xff; 8], 1024),
+            Err(Error::InflateError(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_concatenated_zlib_streams() -> Result<(), Error> {
+        let mut data = ContentEncoding::Deflate.encode(b"#main _ _: int-add(1 2) ");
+        data.extend(ContentEncoding::Deflate.encode(b"int-add(2 3)"));
+
+        assert_eq!(
+            ContentEncoding::Deflate.decode(&data)?,
+            b"#main _ _: int-add(1 2) int-add(2 3)".to_vec()
+        );
+        assert!(matches!(
+            ContentEncoding::Deflate.decode_with_limit(&data, 30),
+            Err(Error::DecompressionLimitExceeded)
+        ));
+
+        // a corrupt second stream is an error
+        data.extend([0x78, 0x9c, 0xff, 0xff]);
+        assert!(matches!(
+            ContentEncoding::Deflate.decode(&data),
+            Err(Error::InflateError(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_brotli_and_zstd_roundtrip() -> Result<(), Error> {
+        let data = b"#main _ _: int-add(1 2) int-add(2 3);".repeat(20);
+        for content_encoding in [ContentEncoding::Brotli, ContentEncoding::Zstd] {
+            let encoded = content_encoding.encode(&data);
+            assert!(encoded.len() < data.len());
+            assert_eq!(content_encoding.decode(&encoded)?, data);
+            assert!(matches!(
+                content_encoding.decode_with_limit(&encoded, 16),
+                Err(Error::DecompressionLimitExceeded)
+            ));
+        }
+        assert_eq!(ContentEncoding::Brotli.to_string(), "br");
+        assert_eq!(
+            "zstd".parse::<ContentEncoding>().unwrap(),
+            ContentEncoding::Zstd
+        );
+        assert!(matches!(
+            ContentEncoding::Zstd.decode(&[0xff; 8]),
+            Err(Error::InflateError(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_autodetect() -> Result<(), Error> {
+        let data = b"#main _ _: int-add(1 2) int-add(2 3);".repeat(20);
+        for content_encoding in [
+            ContentEncoding::Deflate,
+            ContentEncoding::Gzip,
+            ContentEncoding::Brotli,
+            ContentEncoding::Zstd,
+        ] {
+            let encoded = content_encoding.encode(&data);
+            assert_eq!(ContentEncoding::decode_autodetect(&encoded)?, data);
+        }
+
+        // raw deflate without zlib header is caught by the fallback
+        let mut encoder = flate2::write::DeflateEncoder::new(vec![], Compression::default());
+        encoder.write_all(&data).unwrap();
+        let raw = encoder.finish().unwrap();
+        assert_eq!(ContentEncoding::decode_autodetect(&raw)?, data);
+
+        assert!(matches!(
+            ContentEncoding::decode_autodetect(&[0xff; 8]),
+            Err(Error::InflateError(_))
+        ));
+
+        Ok(())
+    }
+
     /// Roundtrip test for a meta sequence
     /// original content -> pack -> MetaMap -> cbor encode -> cbor decode -> MetaMap -> unpack -> original content,
     #[test]
@@ -1232,6 +3549,2145 @@ mod tests {
         Ok(())
     }
 
+    /// Roundtrip test for a json content type meta
+    /// original content -> pack -> MetaMap -> cbor encode -> cbor decode -> MetaMap -> unpack -> original content,
+    #[test]
+    fn json_meta_roundtrip() -> Result<(), Error> {
+        let solidity_abi_content = r#"[
+            {
+                "type": "function",
+                "name": "foo",
+                "inputs": [{ "internalType": "uint256", "name": "a", "type": "uint256" }],
+                "outputs": [],
+                "stateMutability": "view"
+            }
+        ]"#;
+        let solidity_abi_meta: SolidityAbiMeta = serde_json::from_str(solidity_abi_content)?;
+        let json_bytes = serde_json::to_vec(&solidity_abi_meta)?;
+
+        let meta_map = RainMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from(json_bytes.clone()),
+            magic: KnownMagic::SolidityAbiV2,
+            content_type: ContentType::Json,
+            content_encoding: ContentEncoding::None,
+            content_language: ContentLanguage::None,
+        };
+        let cbor_encoded = meta_map.cbor_encode()?;
+
+        // cbor map with 3 keys
+        assert_eq!(cbor_encoded[0], 0xa3);
+        // key 2 followed by text string application/json length 16, must be the end of data
+        let content_type_index = cbor_encoded.len() - 18;
+        assert_eq!(cbor_encoded[content_type_index], 0x02);
+        assert_eq!(cbor_encoded[content_type_index + 1], 0b011_10000);
+        assert_eq!(
+            &cbor_encoded[content_type_index + 2..],
+            "application/json".as_bytes()
+        );
+
+        // decode the data back to MetaMap
+        let mut cbor_decoded = RainMetaDocumentV1Item::cbor_decode(&cbor_encoded)?;
+        assert_eq!(cbor_decoded.len(), 1);
+        assert_eq!(cbor_decoded[0], meta_map);
+        assert_eq!(cbor_decoded[0].unpack()?, json_bytes);
+
+        // unpack the payload as json
+        let unpacked_json: SolidityAbiMeta = cbor_decoded[0].unpack_json()?;
+        assert_eq!(unpacked_json, solidity_abi_meta);
+
+        // unpack the payload into SolidityAbiMeta
+        let unpacked_payload: SolidityAbiMeta = cbor_decoded.pop().unwrap().unpack_into()?;
+        assert_eq!(unpacked_payload, solidity_abi_meta);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_store_default_subgraphs_env_var() {
+        assert_eq!(
+            subgraphs_from_env(Some(
+                "https://example.com/subgraph-1, https://example.com/subgraph-2,"
+            )),
+            vec![
+                "https://example.com/subgraph-1".to_string(),
+                "https://example.com/subgraph-2".to_string(),
+            ]
+        );
+
+        let npe2 = KnownSubgraphs::NPE2.map(|url| url.to_string()).to_vec();
+        assert_eq!(subgraphs_from_env(None), npe2);
+        assert_eq!(subgraphs_from_env(Some(" ")), npe2);
+    }
+
+    #[test]
+    fn test_store_set_subgraphs() {
+        let mut store = Store::new();
+        store.add_subgraphs(&vec!["https://example.com/subgraph-1".to_string()]);
+        store.set_subgraphs(vec![
+            "https://example.com/subgraph-2".to_string(),
+            "https://example.com/subgraph-3".to_string(),
+            "https://example.com/subgraph-2".to_string(),
+        ]);
+        assert_eq!(
+            store.subgraphs(),
+            &vec![
+                "https://example.com/subgraph-2".to_string(),
+                "https://example.com/subgraph-3".to_string(),
+            ]
+        );
+
+        store.set_subgraphs(vec![]);
+        assert!(store.subgraphs().is_empty());
+    }
+
+    #[test]
+    fn test_to_bytes() -> Result<(), Error> {
+        let meta_map = RainMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from("_: 1;".as_bytes().to_vec()),
+            magic: KnownMagic::RainlangV1,
+            content_type: ContentType::OctetStream,
+            content_encoding: ContentEncoding::None,
+            content_language: ContentLanguage::None,
+        };
+        let bytes = meta_map.to_bytes()?;
+        assert_eq!(bytes.to_vec(), meta_map.cbor_encode()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_data_uri_roundtrip() -> Result<(), Error> {
+        let meta_map = RainMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from(ContentEncoding::Deflate.encode(b"_: add(1 2);")),
+            magic: KnownMagic::RainlangV1,
+            content_type: ContentType::OctetStream,
+            content_encoding: ContentEncoding::Deflate,
+            content_language: ContentLanguage::En,
+        };
+
+        let uri = meta_map.to_data_uri()?;
+        assert!(uri.starts_with("data:application/cbor;base64,"));
+        assert_eq!(RainMetaDocumentV1Item::from_data_uri(&uri)?, meta_map);
+
+        assert!(matches!(
+            RainMetaDocumentV1Item::from_data_uri("data:application/json;base64,e30="),
+            Err(Error::InvalidDataUri)
+        ));
+        assert!(matches!(
+            RainMetaDocumentV1Item::from_data_uri("data:application/cbor;base64,!!"),
+            Err(Error::Base64DecodeError(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_store_create_parallel() -> Result<(), Error> {
+        let mut cache = HashMap::new();
+        let mut dotrain_cache = HashMap::new();
+        for i in 0..2000u32 {
+            let meta_bytes = RainMetaDocumentV1Item::cbor_encode_seq(
+                &vec![RainMetaDocumentV1Item {
+                    payload: serde_bytes::ByteBuf::from(format!("_: {};", i).into_bytes()),
+                    magic: KnownMagic::RainlangV1,
+                    content_type: ContentType::OctetStream,
+                    content_encoding: ContentEncoding::None,
+                    content_language: ContentLanguage::None,
+                }],
+                KnownMagic::RainMetaDocumentV1,
+            )?;
+            let hash = keccak256(&meta_bytes).0.to_vec();
+            if i % 10 == 0 {
+                // invalid items that should be ignored
+                cache.insert(hash, vec![i as u8]);
+            } else {
+                dotrain_cache.insert(format!("path/to/{}.rain", i), hash.clone());
+                cache.insert(hash, meta_bytes);
+            }
+        }
+
+        let subgraphs = vec!["https://example.com/subgraph".to_string()];
+        let serial = Store::create(&subgraphs, &cache, &HashMap::new(), &dotrain_cache, false);
+        let parallel =
+            Store::create_parallel(&subgraphs, &cache, &HashMap::new(), &dotrain_cache, false);
+
+        assert_eq!(parallel, serial);
+        // each valid doc adds its own item and the single meta item inside it
+        assert_eq!(parallel.cache().len(), 1800 * 2);
+        assert_eq!(parallel.dotrain_cache().len(), 1800);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cbor_decode_truncated_and_mutated() -> Result<(), Error> {
+        // same as fuzz corpus seed of fuzz_cbor_decode target
+        let data = RainMetaDocumentV1Item::cbor_encode_seq(
+            &vec![
+                RainMetaDocumentV1Item {
+                    payload: serde_bytes::ByteBuf::from(
+                        ContentEncoding::Deflate.encode(b"#main _ _: int-add(1 2) int-add(2 3)"),
+                    ),
+                    magic: KnownMagic::DotrainV1,
+                    content_type: ContentType::OctetStream,
+                    content_encoding: ContentEncoding::Deflate,
+                    content_language: ContentLanguage::En,
+                },
+                RainMetaDocumentV1Item {
+                    payload: serde_bytes::ByteBuf::from("_: 1;".as_bytes().to_vec()),
+                    magic: KnownMagic::RainlangV1,
+                    content_type: ContentType::OctetStream,
+                    content_encoding: ContentEncoding::None,
+                    content_language: ContentLanguage::None,
+                },
+            ],
+            KnownMagic::RainMetaDocumentV1,
+        )?;
+        let boundaries: Vec<usize> = RainMetaDocumentV1Item::cbor_decode_with_offsets(&data)?
+            .into_iter()
+            .map(|(_, range)| range.end)
+            .collect();
+
+        // every truncation that doesn't end on an item boundary must error
+        for i in 0..data.len() {
+            let result = RainMetaDocumentV1Item::cbor_decode(&data[..i]);
+            assert_eq!(result.is_ok(), boundaries.contains(&i), "at length {}", i);
+        }
+
+        // every single byte mutation must not panic
+        for i in 0..data.len() {
+            for byte in [0x00, 0x1f, 0x5f, 0x7f, 0x9f, 0xbf, 0xff] {
+                let mut mutated = data.clone();
+                mutated[i] = byte;
+                if let Ok(metas) = RainMetaDocumentV1Item::cbor_decode(&mutated) {
+                    for meta in metas {
+                        let _ = meta.unpack();
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cbor_decode_deep_nesting() {
+        // rain meta document with 100k nested single item arrays as its content,
+        // decoding must fail with an error rather than overflowing the stack
+        let mut data = KnownMagic::RainMetaDocumentV1.to_prefix_bytes().to_vec();
+        data.extend(vec![0x81; 100_000]);
+        data.push(0x00);
+        assert!(matches!(
+            RainMetaDocumentV1Item::cbor_decode(&data),
+            Err(Error::MaxDepthExceeded(MAX_CBOR_NESTING_DEPTH))
+        ));
+
+        // same for a nested map as the payload of a meta item
+        let mut data = vec![0xa2, 0x00];
+        data.extend([0xa1, 0x00].repeat(100_000));
+        data.push(0x00);
+        data.extend([0x01, 0x00]);
+        assert!(matches!(
+            RainMetaDocumentV1Item::cbor_decode(&data),
+            Err(Error::MaxDepthExceeded(MAX_CBOR_NESTING_DEPTH))
+        ));
+        assert!(matches!(
+            RawMetaDocumentV1Item::cbor_decode(&data),
+            Err(Error::MaxDepthExceeded(MAX_CBOR_NESTING_DEPTH))
+        ));
+
+        // and for indefinite length arrays and tags
+        let data = [0x9f, 0xc6].repeat(MAX_CBOR_NESTING_DEPTH);
+        assert!(matches!(
+            RainMetaDocumentV1Item::cbor_decode(&data),
+            Err(Error::MaxDepthExceeded(MAX_CBOR_NESTING_DEPTH))
+        ));
+
+        // nesting up to the max depth is left to the decoder, this one is not a meta map
+        let mut data = vec![0x81; MAX_CBOR_NESTING_DEPTH - 1];
+        data.extend([0xa1, 0x00, 0x00]);
+        assert!(check_cbor_depth(&data, MAX_CBOR_NESTING_DEPTH).is_ok());
+        assert!(check_cbor_depth(&data, MAX_CBOR_NESTING_DEPTH - 1).is_err());
+        assert!(matches!(
+            RainMetaDocumentV1Item::cbor_decode(&data),
+            Err(Error::SerdeCborError(_))
+        ));
+
+        // closed containers don't count towards the depth of their siblings
+        let data = [0x82, 0x81, 0x00, 0x9f, 0x41, 0xaa, 0xff];
+        assert!(check_cbor_depth(&data, 2).is_ok());
+        assert!(check_cbor_depth(&data, 1).is_err());
+    }
+
+    #[test]
+    fn test_store_get_dotrain_meta_items() -> Result<(), Error> {
+        let mut store = Store::new();
+        store.set_dotrain("_: 1;", "path/to/file.rain", false)?;
+
+        assert_eq!(
+            store.get_dotrain_meta_items("path/to/file.rain").unwrap()?,
+            vec![RainMetaDocumentV1Item::dotrain("_: 1;")]
+        );
+        assert!(store.get_dotrain_meta_items("path/to/other.rain").is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_store_set_dotrain_encoded() -> Result<(), Error> {
+        let text = "#main _ _: int-add(1 2) int-add(2 3);\n".repeat(50);
+        let mut store = Store::new();
+        let (plain_hash, _) = store.set_dotrain(&text, "path/to/plain.rain", false)?;
+        let (hash, _) = store.set_dotrain_encoded(
+            &text,
+            "path/to/file.rain",
+            false,
+            ContentEncoding::Deflate,
+        )?;
+
+        let bytes = store.get_dotrain_meta("path/to/file.rain").unwrap();
+        assert_eq!(keccak256(bytes).0.to_vec(), hash);
+        assert!(bytes.len() < store.get_meta(&plain_hash).unwrap().len());
+
+        let items = store.get_dotrain_meta_items("path/to/file.rain").unwrap()?;
+        assert_eq!(items[0].content_encoding, ContentEncoding::Deflate);
+        assert_eq!(store.get_dotrain_text("path/to/file.rain").unwrap()?, text);
+        assert_eq!(store.get_dotrain_text("path/to/plain.rain").unwrap()?, text);
+        assert!(store.get_dotrain_text("path/to/other.rain").is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_store_get_meta_validated() -> Result<(), Error> {
+        let mut store = Store::new();
+        let (hash, _) = store.set_dotrain("_: 1;", "path/to/file.rain", false)?;
+        let corrupt_hash = vec![1u8; 32];
+        store
+            .cache
+            .insert(corrupt_hash.clone(), vec![0xa2, 0x00, 0x41]);
+
+        assert_eq!(
+            store.get_meta_validated(&hash).unwrap()?,
+            store.get_meta(&hash).unwrap()
+        );
+        assert!(matches!(
+            store.get_meta_validated(&corrupt_hash),
+            Some(Err(Error::CorruptMeta))
+        ));
+        assert!(store.get_meta_validated(&[2u8; 32]).is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cbor_decode_with_offsets() -> Result<(), Error> {
+        let items = vec![
+            RainMetaDocumentV1Item {
+                payload: serde_bytes::ByteBuf::from("_: 1;".as_bytes().to_vec()),
+                magic: KnownMagic::RainlangV1,
+                content_type: ContentType::OctetStream,
+                content_encoding: ContentEncoding::None,
+                content_language: ContentLanguage::None,
+            },
+            RainMetaDocumentV1Item {
+                payload: serde_bytes::ByteBuf::from("#main _: 1;".as_bytes().to_vec()),
+                magic: KnownMagic::DotrainV1,
+                content_type: ContentType::OctetStream,
+                content_encoding: ContentEncoding::None,
+                content_language: ContentLanguage::En,
+            },
+        ];
+
+        // with rain meta document prefix
+        let data = RainMetaDocumentV1Item::cbor_encode_seq(&items, KnownMagic::RainMetaDocumentV1)?;
+        let decoded = RainMetaDocumentV1Item::cbor_decode_with_offsets(&data)?;
+        assert_eq!(decoded.len(), 2);
+        let first_len = items[0].cbor_encode()?.len();
+        assert_eq!(decoded[0].0, items[0]);
+        assert_eq!(decoded[0].1, 8..8 + first_len);
+        assert_eq!(decoded[1].0, items[1]);
+        assert_eq!(decoded[1].1, 8 + first_len..data.len());
+        for (meta, range) in &decoded {
+            assert_eq!(data[range.clone()], meta.cbor_encode()?);
+        }
+
+        // without prefix
+        let data = items[1].cbor_encode()?;
+        let decoded = RainMetaDocumentV1Item::cbor_decode_with_offsets(&data)?;
+        assert_eq!(decoded, vec![(items[1].clone(), 0..data.len())]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sequence_to_jsonl() -> Result<(), Error> {
+        let items = vec![
+            RainMetaDocumentV1Item::rainlang("_: 1;"),
+            RainMetaDocumentV1Item {
+                payload: serde_bytes::ByteBuf::from(vec![1, 2]),
+                magic: KnownMagic::ExpressionDeployerV2BytecodeV1,
+                content_type: ContentType::None,
+                content_encoding: ContentEncoding::Identity,
+                content_language: ContentLanguage::En,
+            },
+        ];
+        let jsonl = RainMetaDocumentV1Item::sequence_to_jsonl(&items)?;
+        let lines: Vec<&str> = jsonl.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                r#"{"content_encoding":null,"content_language":null,"content_type":"application/octet-stream","magic":"rainlang-v1","payload":"0x5f3a20313b"}"#,
+                r#"{"content_encoding":"identity","content_language":"en","content_type":null,"magic":"expression-deployer-v2-bytecode-v1","payload":"0x0102"}"#,
+            ]
+        );
+        assert!(jsonl.ends_with('\n'));
+        assert_eq!(RainMetaDocumentV1Item::sequence_to_jsonl(&[])?, "");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cbor_decode_indefinite_length_map() -> Result<(), Error> {
+        // re-encodes a definite length map (major type 5 with up to 23 entries) as an
+        // indefinite length one, i.e. 0xbf followed by the entries and a 0xff break byte
+        let indefinite = |item: &RainMetaDocumentV1Item| -> Result<Vec<u8>, Error> {
+            let mut bytes = item.cbor_encode()?;
+            assert_eq!(bytes[0] & 0xe0, 0xa0);
+            bytes[0] = 0xbf;
+            bytes.push(0xff);
+            Ok(bytes)
+        };
+        let items = [
+            RainMetaDocumentV1Item::rainlang("_: 1;"),
+            RainMetaDocumentV1Item::dotrain("#main _: 1;"),
+        ];
+
+        let data = indefinite(&items[0])?;
+        assert_eq!(
+            RainMetaDocumentV1Item::cbor_decode_with_offsets(&data)?,
+            vec![(items[0].clone(), 0..data.len())]
+        );
+
+        // a sequence mixing indefinite and definite length maps
+        let first = indefinite(&items[0])?;
+        let mut data = KnownMagic::RainMetaDocumentV1.to_prefix_bytes().to_vec();
+        data.extend(&first);
+        data.extend(items[1].cbor_encode()?);
+        let decoded = RainMetaDocumentV1Item::cbor_decode_with_offsets(&data)?;
+        assert_eq!(
+            decoded,
+            vec![
+                (items[0].clone(), 8..8 + first.len()),
+                (items[1].clone(), 8 + first.len()..data.len()),
+            ]
+        );
+
+        // a missing break byte is a truncated item
+        assert!(matches!(
+            RainMetaDocumentV1Item::cbor_decode(&first[..first.len() - 1]),
+            Err(Error::CorruptMeta)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cbor_decode_iter() -> Result<(), Error> {
+        let items = vec![
+            RainMetaDocumentV1Item {
+                payload: serde_bytes::ByteBuf::from("_: 1;".as_bytes().to_vec()),
+                magic: KnownMagic::RainlangV1,
+                content_type: ContentType::OctetStream,
+                content_encoding: ContentEncoding::None,
+                content_language: ContentLanguage::None,
+            },
+            RainMetaDocumentV1Item {
+                payload: serde_bytes::ByteBuf::from("#main _: 1;".as_bytes().to_vec()),
+                magic: KnownMagic::DotrainV1,
+                content_type: ContentType::OctetStream,
+                content_encoding: ContentEncoding::None,
+                content_language: ContentLanguage::En,
+            },
+        ];
+        let data = RainMetaDocumentV1Item::cbor_encode_seq(&items, KnownMagic::RainMetaDocumentV1)?;
+
+        let decoded =
+            RainMetaDocumentV1Item::cbor_decode_iter(&data).collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(decoded, items);
+
+        let found = RainMetaDocumentV1Item::cbor_decode_iter(&data)
+            .find(|r| matches!(r, Ok(m) if m.magic == KnownMagic::DotrainV1));
+        assert_eq!(found.unwrap()?, items[1]);
+
+        // items before the corruption are yielded, then the error
+        let mut corrupt_data = data.clone();
+        corrupt_data.push(0xa2);
+        let mut iter = RainMetaDocumentV1Item::cbor_decode_iter(&corrupt_data);
+        assert_eq!(iter.next().unwrap()?, items[0]);
+        assert_eq!(iter.next().unwrap()?, items[1]);
+        assert!(matches!(iter.next(), Some(Err(Error::CorruptMeta))));
+        assert!(iter.next().is_none());
+        assert!(matches!(
+            RainMetaDocumentV1Item::cbor_decode(&corrupt_data),
+            Err(Error::CorruptMeta)
+        ));
+
+        // empty document
+        let mut iter = RainMetaDocumentV1Item::cbor_decode_iter(&[]);
+        assert!(matches!(iter.next(), Some(Err(Error::CorruptMeta))));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_store_expire_older_than() -> Result<(), Error> {
+        let meta_bytes = RainMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from("_: 1;".as_bytes().to_vec()),
+            magic: KnownMagic::RainlangV1,
+            content_type: ContentType::OctetStream,
+            content_encoding: ContentEncoding::None,
+            content_language: ContentLanguage::None,
+        }
+        .cbor_encode()?;
+        let hash = keccak256(&meta_bytes).0.to_vec();
+
+        let response = from_str::<Value>(&format!(
+            r#"{{"data":{{"meta":{{"__typename":"RainMetaV1","rawBytes":"{}"}}}}}}"#,
+            hex::encode_prefixed(&meta_bytes)
+        ))?;
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method(POST).path("/sg");
+            then.json_body_obj(&response);
+        });
+
+        let mut store = Store::new();
+        store.add_subgraphs(&vec![server.url("/sg")]);
+        assert!(store.update(&hash).await.is_some());
+        let (local_hash, _) = store.set_dotrain("_: 2;", "path/to/file.rain", false)?;
+
+        // nothing is old enough yet
+        assert_eq!(store.expire_older_than(Duration::from_secs(60)), 0);
+        assert!(store.get_meta(&hash).is_some());
+
+        // backdate the subgraph sourced meta, which is also mapped to a dotrain uri
+        store
+            .fetched_at
+            .insert(hash.clone(), SystemTime::now() - Duration::from_secs(3600));
+        store
+            .dotrain_cache
+            .insert("path/to/fetched.rain".to_string(), hash.clone());
+        assert_eq!(store.expire_older_than(Duration::from_secs(60)), 1);
+        assert!(store.get_meta(&hash).is_none());
+        assert!(store.get_meta(&local_hash).is_some());
+        assert!(!store.fetched_at.contains_key(&hash));
+        assert_eq!(store.recency.last_used(&hash), 0);
+        assert_eq!(
+            store.dotrain_cache,
+            HashMap::from([("path/to/file.rain".to_string(), local_hash)])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_store_remove_meta_and_clear() -> Result<(), Error> {
+        let mut store = Store::new();
+        store.add_subgraphs(&vec!["https://example.com/subgraph".to_string()]);
+        let (hash, _) = store.set_dotrain("_: 1;", "path/to/file.rain", false)?;
+        store.set_dotrain("_: 1;", "path/to/copy.rain", false)?;
+        let (other_hash, _) = store.set_dotrain("_: 2;", "path/to/other.rain", false)?;
+        store.deployer_hash_map.insert(vec![1u8; 32], hash.clone());
+
+        let removed = store.remove_meta(&hash);
+        assert_eq!(
+            removed,
+            Some(
+                RainMetaDocumentV1Item {
+                    payload: serde_bytes::ByteBuf::from("_: 1;".as_bytes().to_vec()),
+                    magic: KnownMagic::DotrainV1,
+                    content_type: ContentType::OctetStream,
+                    content_encoding: ContentEncoding::None,
+                    content_language: ContentLanguage::None,
+                }
+                .cbor_encode()?
+            )
+        );
+        assert!(store.get_meta(&hash).is_none());
+        assert!(store.get_dotrain_hash("path/to/file.rain").is_none());
+        assert!(store.get_dotrain_hash("path/to/copy.rain").is_none());
+        assert!(store.deployer_hash_map.is_empty());
+        assert_eq!(
+            store.get_dotrain_hash("path/to/other.rain"),
+            Some(&other_hash)
+        );
+        assert!(store.remove_meta(&hash).is_none());
+
+        store.clear();
+        assert!(store.cache().is_empty());
+        assert!(store.dotrain_cache().is_empty());
+        assert!(store.deployer_cache().is_empty());
+        assert_eq!(
+            store.subgraphs(),
+            &vec!["https://example.com/subgraph".to_string()]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_store_shrink_after_expire() -> Result<(), Error> {
+        let mut store = Store::new();
+        let mut hashes = vec![];
+        for i in 0..1000u32 {
+            let bytes = RainMetaDocumentV1Item {
+                payload: serde_bytes::ByteBuf::from(format!("_: {};", i).into_bytes()),
+                magic: KnownMagic::RainlangV1,
+                content_type: ContentType::OctetStream,
+                content_encoding: ContentEncoding::None,
+                content_language: ContentLanguage::None,
+            }
+            .cbor_encode()?;
+            let hash = keccak256(&bytes).0.to_vec();
+            store.update_with(&hash, &bytes);
+            hashes.push(hash);
+        }
+        // mark all but one as old subgraph sourced metas
+        for hash in &hashes[1..] {
+            store
+                .fetched_at
+                .insert(hash.clone(), SystemTime::now() - Duration::from_secs(3600));
+        }
+        let cache_capacity = store.cache.capacity();
+        let fetched_at_capacity = store.fetched_at.capacity();
+
+        assert_eq!(store.expire_older_than(Duration::from_secs(60)), 999);
+        assert_eq!(store.cache().len(), 1);
+        assert!(store.cache.capacity() < cache_capacity);
+        assert!(store.fetched_at.capacity() < fetched_at_capacity);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_store_save_load_path() -> Result<(), Error> {
+        let mut store = Store::new();
+        store.add_subgraphs(&vec!["https://example.com/subgraph".to_string()]);
+        store.set_dotrain("_: 1;", "path/to/file.rain", false)?;
+        let (hash, _) = store.set_dotrain("_: 2;", "path/to/other.rain", false)?;
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("store.cbor");
+        store.save_to_path(&path)?;
+        assert_eq!(Store::load_from_path(&path)?, store);
+
+        // corrupt entries are dropped on load
+        let mut corrupt_store = store.clone();
+        corrupt_store.cache.insert(hash.clone(), vec![1, 2, 3]);
+        corrupt_store.save_to_path(&path)?;
+        let loaded = Store::load_from_path(&path)?;
+        assert!(loaded.get_meta(&hash).is_none());
+        assert!(loaded.get_dotrain_hash("path/to/other.rain").is_none());
+        assert!(loaded.get_dotrain_hash("path/to/file.rain").is_some());
+        assert_eq!(loaded.cache().len(), store.cache().len() - 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_store_merge_with_policy() -> Result<(), Error> {
+        let mut old_store = Store::new();
+        let (old_hash, _) = old_store.set_dotrain("_: 1;", "path/to/file.rain", false)?;
+        let mut new_store = Store::new();
+        let (new_hash, _) = new_store.set_dotrain("_: 2;", "path/to/file.rain", false)?;
+        new_store.set_dotrain("_: 3;", "path/to/other.rain", false)?;
+
+        let mut store = old_store.clone();
+        store.merge(&new_store);
+        assert_eq!(store.get_dotrain_hash("path/to/file.rain"), Some(&old_hash));
+        assert!(store.get_dotrain_hash("path/to/other.rain").is_some());
+
+        let mut store = old_store.clone();
+        store.merge_with(&new_store, MergePolicy::PreferOther);
+        assert_eq!(store.get_dotrain_hash("path/to/file.rain"), Some(&new_hash));
+        assert!(store.get_dotrain_hash("path/to/other.rain").is_some());
+        // the merged uri resolves to the other store's meta
+        assert_eq!(
+            store.get_dotrain_meta("path/to/file.rain"),
+            new_store.get_dotrain_meta("path/to/file.rain")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_store_capacity_used() -> Result<(), Error> {
+        let mut store = Store::with_capacity(1024);
+        assert_eq!(store.capacity_used(), (0, Some(1024)));
+
+        let (hash_1, _) = store.set_dotrain("_: 1;", "path/to/one.rain", false)?;
+        let size_1 = store.get_meta(&hash_1).unwrap().len();
+        assert_eq!(store.capacity_used(), (size_1, Some(1024)));
+
+        let (hash_2, _) = store.set_dotrain("_: 22;", "path/to/two.rain", false)?;
+        let size_2 = store.get_meta(&hash_2).unwrap().len();
+        assert_eq!(store.capacity_used(), (size_1 + size_2, Some(1024)));
+
+        store.remove_meta(&hash_1);
+        assert_eq!(store.capacity_used(), (size_2, Some(1024)));
+
+        store.clear();
+        assert_eq!(store.capacity_used(), (0, Some(1024)));
+        assert_eq!(Store::new().capacity_used(), (0, None));
+
+        // hashes evicted by the last insert are exposed
+        let meta = |text: &str| -> Result<(Vec<u8>, Vec<u8>), Error> {
+            let bytes = RainMetaDocumentV1Item::rainlang(text).cbor_encode()?;
+            Ok((keccak256(&bytes).0.to_vec(), bytes))
+        };
+        let (hash_a, bytes_a) = meta("_: 1;")?;
+        let (hash_b, bytes_b) = meta("_: 2;")?;
+        let (hash_c, bytes_c) = meta("_: 3;")?;
+        let mut store = Store::with_capacity(bytes_a.len() * 2);
+        store.update_with(&hash_a, &bytes_a);
+        store.update_with(&hash_b, &bytes_b);
+        assert!(store.last_evicted().is_empty());
+        store.update_with(&hash_c, &bytes_c);
+        assert_eq!(store.last_evicted(), std::slice::from_ref(&hash_a));
+        assert_eq!(
+            store.capacity_used(),
+            (bytes_a.len() * 2, Some(bytes_a.len() * 2))
+        );
+
+        // and reset by the next insert
+        store.remove_meta(&hash_b);
+        store.update_with(&hash_a, &bytes_a);
+        assert!(store.last_evicted().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_store_lru_eviction() -> Result<(), Error> {
+        let meta = |text: &str| -> Result<(Vec<u8>, Vec<u8>), Error> {
+            let bytes = RainMetaDocumentV1Item {
+                payload: serde_bytes::ByteBuf::from(text.as_bytes().to_vec()),
+                magic: KnownMagic::RainlangV1,
+                content_type: ContentType::OctetStream,
+                content_encoding: ContentEncoding::None,
+                content_language: ContentLanguage::None,
+            }
+            .cbor_encode()?;
+            Ok((keccak256(&bytes).0.to_vec(), bytes))
+        };
+        let (hash_1, bytes_1) = meta("_: 1;")?;
+        let (hash_2, bytes_2) = meta("_: 2;")?;
+        let (hash_3, bytes_3) = meta("_: 3;")?;
+        let (hash_4, bytes_4) = meta("_: 4;")?;
+        let size = bytes_1.len();
+
+        // pinned dotrain meta plus room for 2 more metas
+        let mut store = Store::with_capacity(0);
+        let (dotrain_hash, _) = store.set_dotrain("_: 0;", "path/to/file.rain", false)?;
+        store.max_bytes = Some(store.get_meta(&dotrain_hash).unwrap().len() + size * 2);
+
+        store.update_with(&hash_1, &bytes_1);
+        store.update_with(&hash_2, &bytes_2);
+        // hash_1 becomes the most recently used
+        assert!(store.get_meta(&hash_1).is_some());
+
+        // inserting past the cap drops the least recently used non pinned meta
+        store.update_with(&hash_3, &bytes_3);
+        assert!(store.get_meta(&hash_2).is_none());
+        assert!(store.get_meta(&hash_1).is_some());
+        assert!(store.get_meta(&hash_3).is_some());
+        assert!(store.get_meta(&dotrain_hash).is_some());
+
+        store.update_with(&hash_4, &bytes_4);
+        assert!(store.get_meta(&hash_1).is_none());
+        assert!(store.get_meta(&hash_3).is_some());
+        assert!(store.get_meta(&hash_4).is_some());
+        assert!(store.get_meta(&dotrain_hash).is_some());
+        assert_eq!(store.cache().len(), 3);
+
+        // unbounded store keeps everything
+        let mut store = Store::new();
+        for (hash, bytes) in [
+            (&hash_1, &bytes_1),
+            (&hash_2, &bytes_2),
+            (&hash_3, &bytes_3),
+        ] {
+            store.update_with(hash, bytes);
+        }
+        assert_eq!(store.cache().len(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_store_update_with_many() -> Result<(), Error> {
+        let meta = |text: &str| -> Result<(Vec<u8>, Vec<u8>), Error> {
+            let bytes = RainMetaDocumentV1Item::dotrain(text).cbor_encode()?;
+            Ok((keccak256(&bytes).0.to_vec(), bytes))
+        };
+        let (hash_1, bytes_1) = meta("_: 1;")?;
+        let (hash_2, bytes_2) = meta("_: 2;")?;
+        let (hash_3, bytes_3) = meta("_: 3;")?;
+        assert_ne!(bytes_2, bytes_3);
+
+        let mut store = Store::new();
+        let stored = store.update_with_many(vec![
+            (hash_1.clone(), bytes_1.clone()),
+            // mismatching hash and bytes
+            (hash_2.clone(), bytes_3.clone()),
+            (hash_3.clone(), bytes_3.clone()),
+            (vec![], vec![]),
+            // duplicate of an entry stored above
+            (hash_1.clone(), bytes_1.clone()),
+        ]);
+        assert_eq!(stored, 2);
+        assert_eq!(store.meta_count(), 2);
+        assert_eq!(store.get_meta(&hash_1), Some(&bytes_1));
+        assert_eq!(store.get_meta(&hash_3), Some(&bytes_3));
+        assert!(!store.contains_meta(&hash_2));
+
+        // already cached entries are not counted as stored
+        assert_eq!(
+            store.update_with_many([(hash_1.clone(), bytes_1.clone()), (hash_2, bytes_2)]),
+            1
+        );
+        assert_eq!(store.meta_count(), 3);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_shared_store_concurrent_update() -> Result<(), Error> {
+        let meta_bytes = RainMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from("_: 1;".as_bytes().to_vec()),
+            magic: KnownMagic::RainlangV1,
+            content_type: ContentType::OctetStream,
+            content_encoding: ContentEncoding::None,
+            content_language: ContentLanguage::None,
+        }
+        .cbor_encode()?;
+        let hash = keccak256(&meta_bytes).0.to_vec();
+        let response = from_str::<Value>(&format!(
+            r#"{{"data":{{"meta":{{"__typename":"RainMetaV1","rawBytes":"{}"}}}}}}"#,
+            hex::encode_prefixed(&meta_bytes)
+        ))?;
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method(POST).path("/sg");
+            then.json_body_obj(&response)
+                .delay(std::time::Duration::from_millis(50));
+        });
+
+        let mut store = Store::new();
+        store.add_subgraphs(&vec![server.url("/sg")]);
+        let (dotrain_hash, _) = store.set_dotrain("_: 2;", "path/to/file.rain", false)?;
+        let shared_store = SharedStore::new(store);
+
+        let mut handles = vec![];
+        for i in 0..8 {
+            let shared_store = shared_store.clone();
+            let hash = hash.clone();
+            let dotrain_hash = dotrain_hash.clone();
+            handles.push(tokio::spawn(async move {
+                if i % 2 == 0 {
+                    shared_store.update_check(&hash).await
+                } else {
+                    // reads are not blocked by the in flight updates
+                    shared_store.get_meta(&dotrain_hash)
+                }
+            }));
+        }
+        let results = tokio::time::timeout(
+            std::time::Duration::from_secs(10),
+            future::join_all(handles),
+        )
+        .await
+        .expect("deadlocked");
+        for (i, result) in results.into_iter().enumerate() {
+            let bytes = result.unwrap().unwrap();
+            if i % 2 == 0 {
+                assert_eq!(bytes, meta_bytes);
+            } else {
+                assert_eq!(
+                    Some(bytes),
+                    shared_store.get_dotrain_meta("path/to/file.rain")
+                );
+            }
+        }
+
+        let snapshot = shared_store.snapshot();
+        assert_eq!(snapshot.get_meta(&hash), Some(&meta_bytes));
+        assert_eq!(snapshot.cache().len(), 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_store_try_update() {
+        let hash = [1u8; 32];
+
+        let failing_server = MockServer::start_async().await;
+        failing_server.mock(|when, then| {
+            when.method(POST).path("/");
+            then.status(500).body("internal server error");
+        });
+        let mut store = Store::new();
+        store.add_subgraphs(&vec![failing_server.url("/")]);
+        assert!(store.try_update(&hash).await.is_err());
+        assert!(store.try_search_deployer_check(&hash).await.is_err());
+
+        let empty_server = MockServer::start_async().await;
+        empty_server.mock(|when, then| {
+            when.method(POST).path("/");
+            then.status(200).json_body_obj(&serde_json::json!({
+                "data": { "meta": null, "expressionDeployers": [] }
+            }));
+        });
+        let mut store = Store::new();
+        store.add_subgraphs(&vec![empty_server.url("/")]);
+        assert!(matches!(store.try_update(&hash).await, Ok(None)));
+        assert!(store.update(&hash).await.is_none());
+        assert!(matches!(
+            store.try_search_deployer_check(&hash).await,
+            Ok(None)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_store_diff_against_subgraph() -> Result<(), Error> {
+        let mut store = Store::new();
+        let (published_hash, _) = store.set_dotrain("_: 1;", "path/to/published.rain", false)?;
+        let (local_hash, _) = store.set_dotrain("_: 2;", "path/to/local.rain", false)?;
+
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .body_contains(hex::encode(&published_hash));
+            then.status(200).json_body_obj(&serde_json::json!({
+                "data": {
+                    "metaV1S": [{
+                        "meta": hex::encode_prefixed(store.get_meta(&published_hash).unwrap()),
+                        "metaHash": hex::encode_prefixed(&published_hash),
+                        "sender": "0x00",
+                        "id": "0x00",
+                        "metaBoard": {
+                            "id": "0x00",
+                            "metas": [],
+                            "address": "0x00",
+                        },
+                        "subject": "0x00",
+                    }]
+                }
+            }));
+        });
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .body_contains(hex::encode(&local_hash));
+            then.status(200).json_body_obj(&serde_json::json!({
+                "data": {
+                    "metaV1S": []
+                }
+            }));
+        });
+
+        let client = MetaboardSubgraphClient::new(server.url("/").parse().unwrap());
+        let unpublished = store.diff_against_subgraph(&client).await?;
+        assert_eq!(
+            unpublished,
+            vec![<[u8; 32]>::try_from(local_hash.clone()).unwrap()]
+        );
+
+        // a single query at a time gives the same result
+        let unpublished = store.diff_against_subgraph_with_limit(&client, 1).await?;
+        assert_eq!(unpublished, vec![<[u8; 32]>::try_from(local_hash).unwrap()]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_traced() -> Result<(), Error> {
+        let meta_bytes = RainMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from("_: 1;".as_bytes().to_vec()),
+            magic: KnownMagic::RainlangV1,
+            content_type: ContentType::OctetStream,
+            content_encoding: ContentEncoding::None,
+            content_language: ContentLanguage::None,
+        }
+        .cbor_encode()?;
+        let hash = hex::encode_prefixed(keccak256(&meta_bytes));
+
+        let empty_server = MockServer::start_async().await;
+        empty_server.mock(|when, then| {
+            when.method(POST).path("/");
+            then.status(200).json_body_obj(&serde_json::json!({
+                "data": { "meta": null }
+            }));
+        });
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method(POST).path("/");
+            then.status(200).json_body_obj(&serde_json::json!({
+                "data": {
+                    "meta": {
+                        "__typename": "RainMetaV1",
+                        "rawBytes": hex::encode_prefixed(&meta_bytes),
+                    }
+                }
+            }));
+        });
+
+        let subgraphs = vec![empty_server.url("/"), server.url("/")];
+        let (response, url) = search_traced(&hash, &subgraphs).await?;
+        assert_eq!(response.bytes, meta_bytes);
+        assert_eq!(url, server.url("/"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_fresh() -> Result<(), Error> {
+        let meta_bytes = RainMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from("_: 1;".as_bytes().to_vec()),
+            magic: KnownMagic::RainlangV1,
+            content_type: ContentType::OctetStream,
+            content_encoding: ContentEncoding::None,
+            content_language: ContentLanguage::None,
+        }
+        .cbor_encode()?;
+        let hash = hex::encode_prefixed(keccak256(&meta_bytes));
+        let response = |block: u64| {
+            serde_json::json!({
+                "data": {
+                    "meta": {
+                        "__typename": "RainMetaV1",
+                        "rawBytes": hex::encode_prefixed(&meta_bytes),
+                    },
+                    "subgraphMeta": { "block": { "number": block } }
+                }
+            })
+        };
+
+        let stale_server = MockServer::start_async().await;
+        stale_server.mock(|when, then| {
+            when.method(POST).path("/");
+            then.status(200).json_body_obj(&response(90));
+        });
+        let fresh_server = MockServer::start_async().await;
+        fresh_server.mock(|when, then| {
+            when.method(POST).path("/");
+            then.status(200)
+                .delay(Duration::from_millis(200))
+                .json_body_obj(&response(110));
+        });
+        let subgraphs = vec![stale_server.url("/"), fresh_server.url("/")];
+
+        // without a minimum block the faster stale subgraph wins
+        let (result, url) = search_traced(&hash, &subgraphs).await?;
+        assert_eq!(result.block_number, Some(90));
+        assert_eq!(url, stale_server.url("/"));
+
+        let result = search_fresh(&hash, &subgraphs, 100).await?;
+        assert_eq!(result.bytes, meta_bytes);
+        assert_eq!(result.block_number, Some(110));
+
+        assert!(matches!(
+            search_fresh(&hash, &subgraphs, 200).await,
+            Err(Error::NoRecordFound)
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_via_metaboard() -> Result<(), Error> {
+        let meta_bytes = RainMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from("_: 1;".as_bytes().to_vec()),
+            magic: KnownMagic::RainlangV1,
+            content_type: ContentType::OctetStream,
+            content_encoding: ContentEncoding::None,
+            content_language: ContentLanguage::None,
+        }
+        .cbor_encode()?;
+        let hash = hex::encode_prefixed(keccak256(&meta_bytes));
+
+        let empty_server = MockServer::start_async().await;
+        empty_server.mock(|when, then| {
+            when.method(POST).path("/");
+            then.status(200).json_body_obj(&serde_json::json!({
+                "data": { "metaV1S": [] }
+            }));
+        });
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method(POST).path("/").body_contains(&hash[2..]);
+            then.status(200).json_body_obj(&serde_json::json!({
+                "data": {
+                    "metaV1S": [{
+                        "meta": hex::encode_prefixed(&meta_bytes),
+                        "metaHash": hash,
+                        "sender": "0x00",
+                        "id": "0x00",
+                        "metaBoard": {
+                            "id": "0x00",
+                            "metas": [],
+                            "address": "0x00",
+                        },
+                        "subject": "0x00",
+                    }]
+                }
+            }));
+        });
+
+        let urls = vec![empty_server.url("/"), server.url("/")];
+        let response = search_via_metaboard(&hash, &urls).await?;
+        assert_eq!(response.bytes, meta_bytes);
+
+        assert!(matches!(
+            search_via_metaboard(&hash, &vec![empty_server.url("/")]).await,
+            Err(Error::NoRecordFound)
+        ));
+        assert!(matches!(
+            search_via_metaboard("0x1234", &urls).await,
+            Err(Error::InvalidHash)
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_documents_by_subject() -> Result<(), Error> {
+        let item = RainMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from("_: 1;".as_bytes().to_vec()),
+            magic: KnownMagic::RainlangV1,
+            content_type: ContentType::OctetStream,
+            content_encoding: ContentEncoding::None,
+            content_language: ContentLanguage::None,
+        };
+        let valid = RainMetaDocumentV1Item::cbor_encode_seq(
+            &vec![item.clone()],
+            KnownMagic::RainMetaDocumentV1,
+        )?;
+        let meta = |bytes: &[u8]| {
+            serde_json::json!({
+                "meta": hex::encode_prefixed(bytes),
+                "metaHash": "0x00",
+                "sender": "0x00",
+                "id": "0x00",
+                "metaBoard": {
+                    "id": "0x00",
+                    "metas": [],
+                    "address": "0x00",
+                },
+                "subject": "1",
+            })
+        };
+
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method(POST).path("/").body_contains("\"1\"");
+            then.status(200).json_body_obj(&serde_json::json!({
+                "data": { "metaV1S": [meta(&valid)] }
+            }));
+        });
+        server.mock(|when, then| {
+            when.method(POST).path("/").body_contains("\"2\"");
+            then.status(200).json_body_obj(&serde_json::json!({
+                "data": { "metaV1S": [meta(&valid), meta(&[0xff, 0x00, 0x01])] }
+            }));
+        });
+        let client = MetaboardSubgraphClient::new(server.url("/").parse().unwrap());
+
+        let documents = get_documents_by_subject(&client, &BigInt("1".to_string())).await?;
+        assert_eq!(documents, vec![vec![item]]);
+
+        assert!(matches!(
+            get_documents_by_subject(&client, &BigInt("2".to_string())).await,
+            Err(Error::InvalidMetaDocument(1, _))
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_store_update_many() -> Result<(), Error> {
+        let server = MockServer::start_async().await;
+        let mut metas = vec![];
+        for i in 0..3 {
+            let meta_bytes = RainMetaDocumentV1Item {
+                payload: serde_bytes::ByteBuf::from(format!("_: {};", i).into_bytes()),
+                magic: KnownMagic::RainlangV1,
+                content_type: ContentType::OctetStream,
+                content_encoding: ContentEncoding::None,
+                content_language: ContentLanguage::None,
+            }
+            .cbor_encode()?;
+            let hash = keccak256(&meta_bytes).0.to_vec();
+            // the subgraph only has the first two
+            if i < 2 {
+                let response = from_str::<Value>(&format!(
+                    r#"{{"data":{{"meta":{{"__typename":"RainMetaV1","rawBytes":"{}"}}}}}}"#,
+                    hex::encode_prefixed(&meta_bytes)
+                ))?;
+                server.mock(|when, then| {
+                    when.method(POST)
+                        .path("/sg")
+                        .body_contains(hex::encode(&hash));
+                    then.json_body_obj(&response);
+                });
+            }
+            metas.push((hash, meta_bytes));
+        }
+
+        let mut store = Store::new();
+        store.add_subgraphs(&vec![server.url("/sg")]);
+        let hashes: Vec<Vec<u8>> = metas.iter().map(|(hash, _)| hash.clone()).collect();
+        let result = store.update_many_with_limit(&hashes, 2).await;
+
+        assert_eq!(result.len(), 3);
+        assert_eq!(result.get(&metas[0].0), Some(&true));
+        assert_eq!(result.get(&metas[1].0), Some(&true));
+        assert_eq!(result.get(&metas[2].0), Some(&false));
+        assert_eq!(store.get_meta(&metas[0].0), Some(&metas[0].1));
+        assert_eq!(store.get_meta(&metas[1].0), Some(&metas[1].1));
+        assert!(store.get_meta(&metas[2].0).is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_subject_keyed_hash_set() -> Result<(), Error> {
+        let content = "#main _ _: int-add(1 2) int-add(2 3)".as_bytes();
+        let plain = RainMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from(content.to_vec()),
+            magic: KnownMagic::DotrainV1,
+            content_type: ContentType::OctetStream,
+            content_encoding: ContentEncoding::None,
+            content_language: ContentLanguage::None,
+        };
+        let deflated = RainMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from(ContentEncoding::Deflate.encode(content)),
+            magic: KnownMagic::DotrainV1,
+            content_type: ContentType::OctetStream,
+            content_encoding: ContentEncoding::Deflate,
+            content_language: ContentLanguage::En,
+        };
+        let other = RainMetaDocumentV1Item {
+            magic: KnownMagic::RainlangV1,
+            ..plain.clone()
+        };
+
+        let mut set = std::collections::HashSet::new();
+        assert!(set.insert(SubjectKeyed::new(plain.clone())?));
+        assert!(!set.insert(SubjectKeyed::try_from(deflated.clone())?));
+        assert_eq!(set.len(), 1);
+        assert!(set.insert(SubjectKeyed::new(other)?));
+        assert_eq!(set.len(), 2);
+
+        let keyed = SubjectKeyed::new(deflated.clone())?;
+        assert_eq!(keyed.subject(), plain.canonical_subject()?);
+        assert_eq!(keyed.item(), &deflated);
+        assert_eq!(keyed.into_inner(), deflated);
+
+        // items that fail to unpack are keyed by their cbor hash instead of colliding
+        let corrupt = |payload: Vec<u8>| RainMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from(payload),
+            content_encoding: ContentEncoding::Deflate,
+            ..plain.clone()
+        };
+        let corrupt_1 = SubjectKeyed::new(corrupt(vec![0xff, 0xff]))?;
+        let corrupt_2 = SubjectKeyed::new(corrupt(vec![0xfe, 0xfe]))?;
+        assert!(corrupt_1.item().unpack().is_err());
+        assert_eq!(corrupt_1.subject(), corrupt_1.item().hash(false)?);
+        assert_ne!(corrupt_1, corrupt_2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_with_opts_retries_timeout() -> Result<(), Error> {
+        let meta_bytes = RainMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from("_: 1;".as_bytes().to_vec()),
+            magic: KnownMagic::RainlangV1,
+            content_type: ContentType::OctetStream,
+            content_encoding: ContentEncoding::None,
+            content_language: ContentLanguage::None,
+        }
+        .cbor_encode()?;
+        let body = format!(
+            r#"{{"data":{{"meta":{{"__typename":"RainMetaV1","rawBytes":"{}"}}}}}}"#,
+            hex::encode_prefixed(&meta_bytes)
+        );
+
+        // a subgraph that hangs on the first request and answers the next ones
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("http://{}/sg", listener.local_addr()?);
+        let hits = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let server_hits = hits.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = match stream {
+                    Ok(v) => v,
+                    Err(_) => break,
+                };
+                let hit = server_hits.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                let body = body.clone();
+                std::thread::spawn(move || {
+                    let mut buf = [0u8; 8192];
+                    let _ = stream.read(&mut buf);
+                    if hit == 0 {
+                        std::thread::sleep(std::time::Duration::from_secs(2));
+                    }
+                    let _ = stream.write_all(
+                        format!(
+                            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                            body.len(),
+                            body
+                        )
+                        .as_bytes(),
+                    );
+                });
+            }
+        });
+
+        let hash = hex::encode_prefixed(keccak256(&meta_bytes));
+        let opts = SearchOpts {
+            timeout: Duration::from_millis(300),
+            retries: 2,
+            backoff: Duration::from_millis(10),
+        };
+        let response = search_with_opts(&hash, &vec![url.clone()], opts).await?;
+        assert_eq!(response.bytes, meta_bytes);
+        assert_eq!(hits.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+        // without retries the hanging first request fails with timeout
+        hits.store(0, std::sync::atomic::Ordering::SeqCst);
+        let opts = SearchOpts { retries: 0, ..opts };
+        assert!(matches!(
+            search_with_opts(&hash, &vec![url], opts).await,
+            Err(Error::Timeout)
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_with_opts_retries_server_error() -> Result<(), Error> {
+        let meta_bytes = RainMetaDocumentV1Item::rainlang("_: 1;").cbor_encode()?;
+        let response = from_str::<Value>(&format!(
+            r#"{{"data":{{"meta":{{"__typename":"RainMetaV1","rawBytes":"{}"}}}}}}"#,
+            hex::encode_prefixed(&meta_bytes)
+        ))?;
+        let hash = hex::encode_prefixed(keccak256(&meta_bytes));
+        let opts = SearchOpts {
+            timeout: Duration::from_secs(5),
+            retries: 2,
+            backoff: Duration::from_millis(300),
+        };
+
+        // a gateway error page on the first request, answered once it is replaced
+        let server = MockServer::start_async().await;
+        let mut gateway_error = server
+            .mock_async(|when, then| {
+                when.method(POST).path("/sg");
+                then.status(503)
+                    .body("<html>503 Service Unavailable</html>");
+            })
+            .await;
+        let replace = async {
+            while gateway_error.hits_async().await == 0 {
+                Delay::new(Duration::from_millis(10)).await;
+            }
+            gateway_error.delete_async().await;
+            server
+                .mock_async(|when, then| {
+                    when.method(POST).path("/sg");
+                    then.json_body_obj(&response);
+                })
+                .await
+        };
+        let (result, ok_mock) = future::join(
+            search_with_opts(&hash, &vec![server.url("/sg")], opts),
+            replace,
+        )
+        .await;
+        assert_eq!(result?.bytes, meta_bytes);
+        ok_mock.assert_hits_async(1).await;
+
+        // without retries the error status is surfaced as is
+        gateway_error = server
+            .mock_async(|when, then| {
+                when.method(POST).path("/sg2");
+                then.status(503)
+                    .body("<html>503 Service Unavailable</html>");
+            })
+            .await;
+        let opts = SearchOpts { retries: 0, ..opts };
+        match search_with_opts(&hash, &vec![server.url("/sg2")], opts).await {
+            Err(Error::ReqwestError(e)) => {
+                assert_eq!(e.status().map(|v| v.as_u16()), Some(503))
+            }
+            other => panic!("expected a 503 error, got {:?}", other),
+        }
+        gateway_error.assert_hits_async(1).await;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_raw_meta_unknown_magic_roundtrip() -> Result<(), Error> {
+        let known = RawMetaDocumentV1Item::from(RainMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from("_: 1;".as_bytes().to_vec()),
+            magic: KnownMagic::DotrainV1,
+            content_type: ContentType::OctetStream,
+            content_encoding: ContentEncoding::None,
+            content_language: ContentLanguage::En,
+        });
+        let unknown = RawMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from(vec![1, 2, 3]),
+            magic: 0xff00112233445566,
+            content_type: ContentType::OctetStream,
+            content_encoding: ContentEncoding::None,
+            content_language: ContentLanguage::None,
+        };
+        let seq = vec![known.clone(), unknown.clone()];
+        let bytes = RawMetaDocumentV1Item::cbor_encode_seq(&seq, KnownMagic::RainMetaDocumentV1)?;
+
+        // the strict decoder rejects the whole document
+        assert!(RainMetaDocumentV1Item::cbor_decode(&bytes).is_err());
+
+        let decoded = RawMetaDocumentV1Item::cbor_decode(&bytes)?;
+        assert_eq!(decoded, seq);
+        assert_eq!(
+            RawMetaDocumentV1Item::cbor_encode_seq(&decoded, KnownMagic::RainMetaDocumentV1)?,
+            bytes
+        );
+
+        assert_eq!(decoded[0].known_magic(), Some(KnownMagic::DotrainV1));
+        assert_eq!(decoded[1].known_magic(), None);
+        assert_eq!(decoded[0].clone().unpack_into::<String>()?, "_: 1;");
+        assert!(matches!(
+            decoded[1].clone().unpack_into::<Vec<u8>>(),
+            Err(Error::UnsupportedMeta)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_item_constructors() -> Result<(), Error> {
+        fn roundtrip(item: RainMetaDocumentV1Item) -> Result<RainMetaDocumentV1Item, Error> {
+            item.validate()?;
+            let mut decoded = RainMetaDocumentV1Item::cbor_decode(&item.cbor_encode()?)?;
+            assert_eq!(decoded, vec![item]);
+            Ok(decoded.remove(0))
+        }
+
+        let item = roundtrip(RainMetaDocumentV1Item::dotrain("#main _: 1;"))?;
+        assert_eq!(item.magic, KnownMagic::DotrainV1);
+        assert_eq!(item.unpack_into::<DotrainMeta>()?, "#main _: 1;");
+
+        let item = roundtrip(RainMetaDocumentV1Item::rainlang("_: 1;"))?;
+        assert_eq!(item.magic, KnownMagic::RainlangV1);
+        assert_eq!(item.unpack_into::<String>()?, "_: 1;");
+
+        let item = roundtrip(RainMetaDocumentV1Item::rainlang_source("_: 2;"))?;
+        assert_eq!(item.magic, KnownMagic::RainlangSourceV1);
+        assert_eq!(item.unpack_into::<String>()?, "_: 2;");
+
+        let authoring_meta = AuthoringMeta(vec![types::authoring::v1::AuthoringMetaItem {
+            word: "stack".to_string(),
+            operand_parser_offset: 16,
+            description: "Copies an existing value from the stack.".to_string(),
+        }]);
+        let item = roundtrip(RainMetaDocumentV1Item::authoring(&authoring_meta)?)?;
+        assert_eq!(item.magic, KnownMagic::AuthoringMetaV1);
+        assert_eq!(item.content_type, ContentType::Cbor);
+        assert_eq!(item.unpack_into::<AuthoringMeta>()?, authoring_meta);
+        let lib_doc_item = lib_doc_authoring_item()?;
+        let lib_doc_meta = lib_doc_item.clone().unpack_into::<AuthoringMeta>()?;
+        assert_eq!(
+            RainMetaDocumentV1Item::authoring(&lib_doc_meta)?,
+            lib_doc_item
+        );
+
+        // words are validated before encoding
+        let non_ascii = AuthoringMeta(vec![types::authoring::v1::AuthoringMetaItem {
+            word: "stäck".to_string(),
+            ..authoring_meta.0[0].clone()
+        }]);
+        assert!(matches!(
+            RainMetaDocumentV1Item::authoring(&non_ascii),
+            Err(Error::InvalidWordEncoding(_))
+        ));
+
+        let abi: SolidityAbiMeta = serde_json::from_str("[]")?;
+        let item = roundtrip(RainMetaDocumentV1Item::solidity_abi(&abi)?)?;
+        assert_eq!(item.magic, KnownMagic::SolidityAbiV2);
+        assert_eq!(
+            serde_json::to_value(item.unpack_into::<SolidityAbiMeta>()?)?,
+            serde_json::to_value(&abi)?
+        );
+
+        let op: types::op::v1::OpMeta = serde_json::from_str(r#"{ "name": "stack" }"#)?;
+        let item = roundtrip(RainMetaDocumentV1Item::op(&op)?)?;
+        assert_eq!(item.magic, KnownMagic::OpMetaV1);
+        assert_eq!(
+            serde_json::to_value(item.unpack_into::<types::op::v1::OpMeta>()?)?,
+            serde_json::to_value(&op)?
+        );
+
+        let caller: types::interpreter_caller::v1::InterpreterCallerMeta = serde_json::from_str(
+            r#"{
+                "name": "Caller",
+                "abiName": "Caller",
+                "methods": [
+                    {
+                        "name": "do something",
+                        "abiName": "doSomething",
+                        "inputs": [
+                            {
+                                "name": "config",
+                                "abiName": "config",
+                                "path": "[0].inputs[0]"
+                            }
+                        ],
+                        "expressions": []
+                    }
+                ]
+            }"#,
+        )?;
+        let item = roundtrip(RainMetaDocumentV1Item::interpreter_caller(&caller)?)?;
+        assert_eq!(item.magic, KnownMagic::InterpreterCallerMetaV1);
+        assert_eq!(
+            serde_json::to_value(
+                item.unpack_into::<types::interpreter_caller::v1::InterpreterCallerMeta>()?
+            )?,
+            serde_json::to_value(&caller)?
+        );
+
+        let item = roundtrip(RainMetaDocumentV1Item::expression_deployer_bytecode(&[
+            0x60, 0x80,
+        ]))?;
+        assert_eq!(item.magic, KnownMagic::ExpressionDeployerV2BytecodeV1);
+        assert_eq!(item.unpack_into::<Vec<u8>>()?, vec![0x60, 0x80]);
+
+        Ok(())
+    }
+
+    // the cbor labelled abi encoded authoring meta item of the lib.rs doc example
+    fn lib_doc_authoring_item() -> Result<RainMetaDocumentV1Item, Error> {
+        let authoring_meta: AuthoringMeta = serde_json::from_str(
+            r#"[
+                {
+                    "word": "stack",
+                    "description": "Copies an existing value from the stack.",
+                    "operandParserOffset": 16
+                },
+                {
+                    "word": "constant",
+                    "description": "Copies a constant value onto the stack.",
+                    "operandParserOffset": 16
+                }
+            ]"#,
+        )?;
+        Ok(RainMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from(authoring_meta.abi_encode_validate()?),
+            magic: KnownMagic::AuthoringMetaV1,
+            content_type: ContentType::Cbor,
+            content_encoding: ContentEncoding::None,
+            content_language: ContentLanguage::None,
+        })
+    }
+
+    #[test]
+    fn test_item_validate() -> Result<(), Error> {
+        let authoring_meta = AuthoringMeta(vec![types::authoring::v1::AuthoringMetaItem {
+            word: "stack".to_string(),
+            operand_parser_offset: 16,
+            description: "Copies an existing value from the stack.".to_string(),
+        }]);
+        let valid_items = [
+            RainMetaDocumentV1Item::builder()
+                .payload("{\"a\": 1}")
+                .magic(KnownMagic::DotrainV1)
+                .content_type(ContentType::Json)
+                .build()?,
+            RainMetaDocumentV1Item::builder()
+                .payload(authoring_meta.abi_encode()?)
+                .magic(KnownMagic::AuthoringMetaV1)
+                .content_type(ContentType::OctetStream)
+                .content_encoding(ContentEncoding::Deflate)
+                .encode_payload()
+                .build()?,
+            RainMetaDocumentV1Item::builder()
+                .payload("[]")
+                .magic(KnownMagic::SolidityAbiV2)
+                .content_type(ContentType::Json)
+                .build()?,
+        ];
+        for item in valid_items {
+            item.validate()?;
+        }
+        lib_doc_authoring_item()?.validate()?;
+
+        let invalid_items = [
+            // not json
+            RainMetaDocumentV1Item::builder()
+                .payload("_: 1;")
+                .magic(KnownMagic::DotrainV1)
+                .content_type(ContentType::Json)
+                .build()?,
+            // not cbor
+            RainMetaDocumentV1Item::builder()
+                .payload(vec![0xff, 0xff])
+                .magic(KnownMagic::ExpressionDeployerV2BytecodeV1)
+                .content_type(ContentType::Cbor)
+                .build()?,
+            // not utf8
+            RainMetaDocumentV1Item::builder()
+                .payload(vec![0xff, 0xfe])
+                .magic(KnownMagic::RainlangV1)
+                .content_type(ContentType::OctetStream)
+                .build()?,
+            // not abi encoded
+            RainMetaDocumentV1Item::builder()
+                .payload("stack")
+                .magic(KnownMagic::AuthoringMetaV1)
+                .content_type(ContentType::OctetStream)
+                .build()?,
+            // not abi encoded while labelled as cbor
+            RainMetaDocumentV1Item::builder()
+                .payload(vec![0x80])
+                .magic(KnownMagic::AuthoringMetaV1)
+                .content_type(ContentType::Cbor)
+                .build()?,
+        ];
+        for item in invalid_items {
+            assert!(matches!(
+                item.validate(),
+                Err(Error::ContentTypeMismatch(_))
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_with_magic() -> Result<(), Error> {
+        let text = "#main _ _: int-add(1 2) int-add(2 3)";
+        let item = RainMetaDocumentV1Item::rainlang_source(text);
+
+        let error = item
+            .clone()
+            .with_magic(KnownMagic::AuthoringMetaV1)
+            .unwrap_err();
+        assert!(matches!(error, Error::ContentTypeMismatch(_)));
+
+        let dotrain = item.with_magic(KnownMagic::DotrainV1)?;
+        assert_eq!(dotrain, RainMetaDocumentV1Item::dotrain(text));
+
+        // cbor labelled abi encoded authoring meta as in the lib.rs doc example
+        let authoring = lib_doc_authoring_item()?;
+        let unknown = RainMetaDocumentV1Item {
+            magic: KnownMagic::RainlangSourceV1,
+            ..authoring.clone()
+        };
+        assert_eq!(unknown.with_magic(KnownMagic::AuthoringMetaV1)?, authoring);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unpack_into_error_context() {
+        let item = RainMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from(vec![0xff, 0xfe]),
+            magic: KnownMagic::DotrainV1,
+            content_type: ContentType::OctetStream,
+            content_encoding: ContentEncoding::None,
+            content_language: ContentLanguage::None,
+        };
+        let error = item.unpack_into::<DotrainMeta>().unwrap_err();
+        assert!(matches!(
+            error,
+            Error::UnpackFailed {
+                magic: KnownMagic::DotrainV1,
+                ..
+            }
+        ));
+        assert!(error.to_string().contains("dotrain-v1"));
+    }
+
+    #[test]
+    fn test_canonicalize_encoding() -> Result<(), Error> {
+        assert_eq!(
+            ContentEncoding::Identity.canonicalize_encoding(),
+            ContentEncoding::None
+        );
+        for content_encoding in ContentEncoding::iter().filter(|v| *v != ContentEncoding::Identity)
+        {
+            assert_eq!(content_encoding.canonicalize_encoding(), content_encoding);
+        }
+
+        let none_meta_map = RainMetaDocumentV1Item::rainlang("_: 1;");
+        let mut identity_meta_map = none_meta_map.clone();
+        identity_meta_map.content_encoding = ContentEncoding::Identity;
+        // the encoding key changes the subject
+        assert_ne!(none_meta_map.hash(false)?, identity_meta_map.hash(false)?);
+
+        identity_meta_map.content_encoding =
+            identity_meta_map.content_encoding.canonicalize_encoding();
+        assert_eq!(none_meta_map.hash(false)?, identity_meta_map.hash(false)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cbor_encode_canonical() -> Result<(), Error> {
+        // same logical state with the map entries in different insertion orders
+        #[derive(serde::Serialize)]
+        struct StateA {
+            name: &'static str,
+            amount: f64,
+            tokens: Vec<u64>,
+        }
+        #[derive(serde::Serialize)]
+        struct StateB {
+            tokens: Vec<u64>,
+            amount: f64,
+            name: &'static str,
+        }
+        let state_a = StateA {
+            name: "gui state",
+            amount: 1.5,
+            tokens: vec![1, 500, u64::MAX],
+        };
+        let state_b = StateB {
+            tokens: vec![1, 500, u64::MAX],
+            amount: 1.5,
+            name: "gui state",
+        };
+        let item = |payload: Vec<u8>| RainMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from(payload),
+            magic: KnownMagic::DotrainV1,
+            content_type: ContentType::Cbor,
+            content_encoding: ContentEncoding::None,
+            content_language: ContentLanguage::None,
+        };
+        let item_a = item(serde_cbor::to_vec(&state_a)?);
+        let item_b = item(serde_cbor::to_vec(&state_b)?);
+        assert_ne!(item_a.cbor_encode()?, item_b.cbor_encode()?);
+        assert_ne!(item_a.hash(false)?, item_b.hash(false)?);
+
+        let canonical_a = item_a.cbor_encode_canonical()?;
+        let canonical_b = item_b.cbor_encode_canonical()?;
+        assert_eq!(canonical_a, canonical_b);
+        assert_eq!(keccak256(&canonical_a), keccak256(&canonical_b));
+
+        // still a valid meta item with the sorted keys and shortest form floats
+        let decoded = RainMetaDocumentV1Item::cbor_decode(&canonical_a)?;
+        let payload: serde_cbor::Value = serde_cbor::from_slice(&decoded[0].payload)?;
+        assert_eq!(payload, serde_cbor::from_slice(&item_a.payload)?);
+        assert_eq!(
+            hex::encode(&decoded[0].payload),
+            concat!(
+                "a3",
+                "646e616d65",
+                "69677569207374617465", // "name": "gui state"
+                "66616d6f756e74",
+                "f93e00", // "amount": 1.5
+                "66746f6b656e73",
+                "83",
+                "01",
+                "1901f4",
+                "1bffffffffffffffff" // "tokens"
+            )
+        );
+
+        // shortest form floats as per RFC 8949 appendix A
+        for (value, expected) in [
+            (0.0, "f90000"),
+            (-0.0, "f98000"),
+            (65504.0, "f97bff"),
+            (5.960464477539063e-8, "f90001"),
+            (0.00006103515625, "f90400"),
+            (100000.0, "fa47c35000"),
+            (1.1, "fb3ff199999999999a"),
+            (f64::INFINITY, "f97c00"),
+            (f64::NAN, "f97e00"),
+        ] {
+            let mut bytes = vec![];
+            write_cbor_canonical(&mut bytes, &serde_cbor::Value::Float(value));
+            assert_eq!(hex::encode(bytes), expected);
+        }
+
+        // already canonical items encode the same as cbor_encode()
+        let dotrain = RainMetaDocumentV1Item::dotrain("#main _ _: int-add(1 2) int-add(2 3)");
+        assert_eq!(dotrain.cbor_encode_canonical()?, dotrain.cbor_encode()?);
+
+        // cbor labelled payloads that are not a single cbor value, such as the abi
+        // encoded authoring meta of the lib.rs doc example, are kept as they are
+        let authoring = lib_doc_authoring_item()?;
+        let canonical = authoring.cbor_encode_canonical()?;
+        assert_eq!(canonical, authoring.cbor_encode()?);
+        assert_eq!(
+            RainMetaDocumentV1Item::cbor_decode(&canonical)?,
+            vec![authoring]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_describe_call_subject() -> Result<(), Error> {
+        let item = RainMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from("#main _ _: int-add(1 2) int-add(2 3)".as_bytes()),
+            magic: KnownMagic::DotrainV1,
+            content_type: ContentType::OctetStream,
+            content_encoding: ContentEncoding::None,
+            content_language: ContentLanguage::En,
+        };
+        let subject = item.describe_call_subject()?;
+        assert_eq!(subject, item.hash(true)?);
+        assert_ne!(subject, item.hash(false)?);
+
+        // keccak256 of the rain meta document emitted for the described contract
+        let meta = RainMetaDocumentV1Item::cbor_encode_seq(
+            &vec![item.clone()],
+            KnownMagic::RainMetaDocumentV1,
+        )?;
+        assert_eq!(subject, keccak256(meta).0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_canonical_subject() -> Result<(), Error> {
+        let dotrain_content = "#main _ _: int-add(1 2) int-add(2 3)";
+        let identity_meta_map = RainMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from(dotrain_content.as_bytes()),
+            magic: KnownMagic::DotrainV1,
+            content_type: ContentType::OctetStream,
+            content_encoding: ContentEncoding::Identity,
+            content_language: ContentLanguage::En,
+        };
+        let deflate_meta_map = RainMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from(
+                ContentEncoding::Deflate.encode(dotrain_content.as_bytes()),
+            ),
+            magic: KnownMagic::DotrainV1,
+            content_type: ContentType::OctetStream,
+            content_encoding: ContentEncoding::Deflate,
+            content_language: ContentLanguage::En,
+        };
+
+        // encoded hashes differ as the payload and content encoding differ
+        assert_ne!(
+            identity_meta_map.hash(false)?,
+            deflate_meta_map.hash(false)?
+        );
+
+        // canonical subjects are the same as the unpacked content is the same
+        assert_eq!(
+            identity_meta_map.canonical_subject()?,
+            deflate_meta_map.canonical_subject()?
+        );
+
+        let mut expected = KnownMagic::DotrainV1.to_prefix_bytes().to_vec();
+        expected.extend(dotrain_content.as_bytes());
+        assert_eq!(
+            identity_meta_map.canonical_subject()?,
+            keccak256(expected).0
+        );
+
+        // a different magic results in a different canonical subject
+        let rainlang_meta_map = RainMetaDocumentV1Item {
+            magic: KnownMagic::RainlangV1,
+            ..identity_meta_map.clone()
+        };
+        assert_ne!(
+            identity_meta_map.canonical_subject()?,
+            rainlang_meta_map.canonical_subject()?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_store_tar_roundtrip() -> Result<(), Error> {
+        let mut store = Store::new();
+        let (dotrain_hash, _) = store.set_dotrain("#main _ _: int-add(1 2)", "./a.rain", false)?;
+        let authoring_meta_bytes = RainMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from(AuthoringMeta(vec![]).abi_encode()?),
+            magic: KnownMagic::AuthoringMetaV1,
+            content_type: ContentType::Cbor,
+            content_encoding: ContentEncoding::None,
+            content_language: ContentLanguage::None,
+        }
+        .cbor_encode()?;
+        let authoring_meta_hash = keccak256(&authoring_meta_bytes).0.to_vec();
+        store.update_with(&authoring_meta_hash, &authoring_meta_bytes);
+
+        let mut archive = vec![];
+        store.export_tar(&mut archive)?;
+
+        let mut imported_store = Store::new();
+        imported_store.import_tar(archive.as_slice())?;
+
+        assert_eq!(imported_store.cache(), store.cache());
+        assert_eq!(imported_store.cache().len(), 2);
+        assert_eq!(
+            imported_store.get_meta(&dotrain_hash),
+            store.get_meta(&dotrain_hash)
+        );
+        assert_eq!(
+            imported_store.get_meta(&authoring_meta_hash),
+            Some(&authoring_meta_bytes)
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_store_hit_miss() -> Result<(), Error> {
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method(POST).path("/");
+            then.status(200).json_body_obj(&serde_json::json!({
+                "data": { "meta": null }
+            }));
+        });
+        let mut store = Store::new();
+        store.add_subgraphs(&vec![server.url("/")]);
+        let (hash, _) = store.set_dotrain("_: 1;", "path/to/file.rain", false)?;
+        assert_eq!(store.hit_miss(), (0, 0));
+
+        assert!(store.get_meta(&hash).is_some());
+        assert!(store.get_meta(&[0u8; 32]).is_none());
+        assert!(store.update_check(&hash).await.is_some());
+        // not found in the subgraph either
+        assert!(store.update_check(&[0u8; 32]).await.is_none());
+        assert_eq!(store.hit_miss(), (2, 2));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_store_hit_miss_internal_lookups() -> Result<(), Error> {
+        let bytes_1 = RainMetaDocumentV1Item::rainlang("_: 1;").cbor_encode()?;
+        let hash_1 = keccak256(&bytes_1).0.to_vec();
+        let bytes_2 = RainMetaDocumentV1Item::rainlang("_: 2;").cbor_encode()?;
+        let hash_2 = keccak256(&bytes_2).0.to_vec();
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method(POST).path("/");
+            then.status(200).json_body_obj(&serde_json::json!({
+                "data": {
+                    "meta": {
+                        "__typename": "RainMetaV1",
+                        "rawBytes": hex::encode_prefixed(&bytes_2),
+                    }
+                }
+            }));
+        });
+
+        // inserts are not lookups
+        let mut store = Store::create(
+            &vec![server.url("/")],
+            &HashMap::from([(hash_1.clone(), bytes_1.clone())]),
+            &HashMap::new(),
+            &HashMap::new(),
+            false,
+        );
+        assert!(store.update_with(&hash_1, &bytes_1).is_some());
+        assert_eq!(store.hit_miss(), (0, 0));
+
+        assert!(store.get_meta(&hash_1).is_some());
+        assert_eq!(store.hit_miss(), (1, 0));
+
+        // a fetched meta counts as a single miss
+        assert_eq!(store.update_check(&hash_2).await, Some(&bytes_2));
+        assert_eq!(store.hit_miss(), (1, 1));
+        assert!(store.get_meta(&hash_2).is_some());
+        assert_eq!(store.hit_miss(), (2, 1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_store_introspection() -> Result<(), Error> {
+        let mut store = Store::new();
+        assert_eq!(store.meta_count(), 0);
+        assert_eq!(store.cache_bytes(), 0);
+
+        let (hash_1, _) = store.set_dotrain("_: 1;", "path/to/one.rain", false)?;
+        let bytes = RainMetaDocumentV1Item::rainlang("_: 22;").cbor_encode()?;
+        let hash_2 = keccak256(&bytes).0.to_vec();
+        store.update_with(&hash_2, &bytes);
+
+        assert_eq!(store.meta_count(), 2);
+        assert_eq!(
+            store.cache_bytes(),
+            store.get_meta(&hash_1).unwrap().len() + bytes.len()
+        );
+        assert_eq!(store.capacity_used().0, store.cache_bytes());
+        assert!(store.contains_meta(&hash_1));
+        assert!(store.contains_meta(&hash_2));
+        assert!(!store.contains_meta(&[0u8; 32]));
+        assert!(store.contains_dotrain("path/to/one.rain"));
+        assert!(!store.contains_dotrain("path/to/two.rain"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_store_deployers() {
+        let mut store = Store::new();
+        assert_eq!(store.deployer_count(), 0);
+        assert!(store.deployers().next().is_none());
+
+        let deployer = |byte: u8| NPE2Deployer {
+            meta_hash: vec![byte; 32],
+            meta_bytes: vec![byte; 4],
+            bytecode: vec![byte; 4],
+            parser: vec![byte; 4],
+            store: vec![byte; 4],
+            interpreter: vec![byte; 4],
+            authoring_meta: None,
+        };
+        store.deployer_cache.insert(vec![1; 32], deployer(1));
+        store.deployer_cache.insert(vec![2; 32], deployer(2));
+
+        assert_eq!(store.deployer_count(), 2);
+        let mut deployers: Vec<_> = store.deployers().collect();
+        deployers.sort_by_key(|(hash, _)| hash.to_vec());
+        assert_eq!(
+            deployers,
+            vec![(&vec![1; 32], &deployer(1)), (&vec![2; 32], &deployer(2))]
+        );
+    }
+
+    #[test]
+    fn test_npe2_deployer_size_bytes() {
+        let mut deployer = NPE2Deployer {
+            meta_hash: vec![1; 32],
+            meta_bytes: vec![2; 100],
+            bytecode: vec![3; 1000],
+            parser: vec![4; 200],
+            store: vec![5; 300],
+            interpreter: vec![6; 400],
+            authoring_meta: None,
+        };
+        assert_eq!(deployer.size_bytes(), 2032);
+
+        deployer.authoring_meta = Some(AuthoringMeta(vec![
+            types::authoring::v1::AuthoringMetaItem {
+                word: "stack".to_string(),
+                operand_parser_offset: 16,
+                description: "Copies an existing value from the stack.".to_string(),
+            },
+        ]));
+        // offset, length, tuple offset, 3 tuple fields, string length and 2 words of string
+        assert_eq!(deployer.size_bytes(), 2032 + 9 * 32);
+    }
+
+    #[tokio::test]
+    async fn test_store_search_deployers() {
+        fn deployer_response(tx_hash: &[u8], bytecode_meta_hash: &[u8]) -> Value {
+            serde_json::json!({
+                "data": {
+                    "expressionDeployers": [{
+                        "constructorMetaHash": hex::encode_prefixed([2u8; 32]),
+                        "constructorMeta": "0x1234",
+                        "deployTransaction": { "id": hex::encode_prefixed(tx_hash) },
+                        "bytecode": "0x01",
+                        "parser": { "parser": { "deployedBytecode": "0x02" } },
+                        "store": { "store": { "deployedBytecode": "0x03" } },
+                        "interpreter": { "interpreter": { "deployedBytecode": "0x04" } },
+                        "meta": [{
+                            "__typename": "RainMetaV1",
+                            "id": hex::encode_prefixed(bytecode_meta_hash)
+                        }]
+                    }]
+                }
+            })
+        }
+
+        let server = MockServer::start_async().await;
+        let hash_1 = vec![5u8; 32];
+        let hash_2 = vec![6u8; 32];
+        let missing_hash = vec![7u8; 32];
+        let tx_hash_1 = vec![8u8; 32];
+        let tx_hash_2 = vec![9u8; 32];
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .body_contains(hex::encode(&hash_1));
+            then.status(200)
+                .json_body(deployer_response(&tx_hash_1, &hash_1));
+        });
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .body_contains(hex::encode(&hash_2));
+            then.status(200)
+                .json_body(deployer_response(&tx_hash_2, &hash_2));
+        });
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .body_contains(hex::encode(&missing_hash));
+            then.status(200)
+                .json_body(serde_json::json!({ "data": { "expressionDeployers": [] } }));
+        });
+
+        let failing_hash = vec![10u8; 32];
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .body_contains(hex::encode(&failing_hash));
+            then.status(500).body("internal error");
+        });
+
+        let mut store = Store::new();
+        store.add_subgraphs(&vec![server.url("/")]);
+        let result = store
+            .search_deployers_with_limit(
+                &[
+                    hash_1.clone(),
+                    hash_2.clone(),
+                    missing_hash.clone(),
+                    failing_hash.clone(),
+                ],
+                2,
+            )
+            .await;
+
+        assert_eq!(result.len(), 4);
+        assert!(matches!(result.get(&hash_1), Some(Ok(true))));
+        assert!(matches!(result.get(&hash_2), Some(Ok(true))));
+        assert!(matches!(result.get(&missing_hash), Some(Ok(false))));
+        // a failing subgraph is not reported as a missing deployer
+        assert!(matches!(result.get(&failing_hash), Some(Err(_))));
+
+        let deployer_1 = store.get_deployer(&hash_1).unwrap();
+        assert_eq!(deployer_1.meta_hash, vec![2u8; 32]);
+        assert_eq!(deployer_1.meta_bytes, vec![0x12, 0x34]);
+        assert_eq!(deployer_1.bytecode, vec![1]);
+        assert_eq!(deployer_1.parser, vec![2]);
+        assert_eq!(deployer_1.store, vec![3]);
+        assert_eq!(deployer_1.interpreter, vec![4]);
+        assert!(store.get_deployer(&hash_2).is_some());
+        assert_eq!(store.get_deployer(&tx_hash_2), store.get_deployer(&hash_2));
+        assert!(store.get_deployer(&missing_hash).is_none());
+    }
+
     #[test]
     fn test_bytes32_to_str() {
         let text_bytes_list = vec![
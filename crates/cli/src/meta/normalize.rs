@@ -20,9 +20,15 @@ impl KnownMeta {
     /// normalizes meta types and also performs validation on those that need validation
     pub fn normalize(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
         Ok(match self {
-            KnownMeta::OpV1 => normalize_json::<OpMeta>(data)?,
+            KnownMeta::OpV1 => serde_json::to_string(&OpMeta::try_from(data.to_vec())?)?
+                .as_bytes()
+                .to_vec(),
             KnownMeta::SolidityAbiV2 => normalize_json::<SolidityAbiMeta>(data)?,
-            KnownMeta::InterpreterCallerMetaV1 => normalize_json::<InterpreterCallerMeta>(data)?,
+            KnownMeta::InterpreterCallerMetaV1 => {
+                serde_json::to_string(&InterpreterCallerMeta::try_from(data)?)?
+                    .as_bytes()
+                    .to_vec()
+            }
             KnownMeta::AuthoringMetaV1 => {
                 // for AuthoringMeta since it can be a json or abi encoded bytes, we try to abi
                 // decode first and then json deserialize if that fails, if either succeeds
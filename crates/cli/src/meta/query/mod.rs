@@ -30,6 +30,9 @@ pub(super) struct DeployerQuery;
 pub struct MetaResponse {
     #[serde(with = "serde_bytes")]
     pub bytes: Vec<u8>,
+    /// block number the subgraph was indexed up to when it answered
+    #[serde(default)]
+    pub block_number: Option<u64>,
 }
 
 /// response data struct for an ExpressionDeployer
@@ -76,30 +79,32 @@ impl DeployerResponse {
 }
 
 /// Process a response for a meta by resolving if a record was found or reject if nothing found or rejected with error
-/// This is because graphql responses are not rejected even if there was no record found for the request
+/// This is because graphql responses are not rejected even if there was no record found for the request,
+/// error status responses are rejected before decoding so they can be retried, see Error::is_retryable()
 pub(super) async fn process_meta_query(
     client: Arc<Client>,
     request_body: &QueryBody<meta_query::Variables>,
     url: &str,
 ) -> Result<MetaResponse, Error> {
+    let data = client
+        .post(url)
+        .json(request_body)
+        .send()
+        .await
+        .map_err(Error::ReqwestError)?
+        .error_for_status()
+        .map_err(Error::ReqwestError)?
+        .json::<Response<meta_query::ResponseData>>()
+        .await
+        .map_err(Error::ReqwestError)?
+        .data
+        .ok_or(Error::NoRecordFound)?;
     Ok(MetaResponse {
-        bytes: decode(
-            client
-                .post(url)
-                .json(request_body)
-                .send()
-                .await
-                .map_err(Error::ReqwestError)?
-                .json::<Response<meta_query::ResponseData>>()
-                .await
-                .map_err(Error::ReqwestError)?
-                .data
-                .ok_or(Error::NoRecordFound)?
-                .meta
-                .ok_or(Error::NoRecordFound)?
-                .raw_bytes,
-        )
-        .or(Err(Error::NoRecordFound))?,
+        bytes: decode(data.meta.ok_or(Error::NoRecordFound)?.raw_bytes)
+            .or(Err(Error::NoRecordFound))?,
+        block_number: data
+            .subgraph_meta
+            .and_then(|v| u64::try_from(v.block.number).ok()),
     })
 }
 
@@ -116,6 +121,8 @@ pub(super) async fn process_deployer_query(
         .send()
         .await
         .map_err(Error::ReqwestError)?
+        .error_for_status()
+        .map_err(Error::ReqwestError)?
         .json::<Response<deployer_query::ResponseData>>()
         .await
         .map_err(Error::ReqwestError)?
@@ -0,0 +1,2 @@
+/// Address list meta V1 implementations
+pub mod v1;
@@ -0,0 +1,88 @@
+use alloy::primitives::Address;
+use serde_bytes::ByteBuf;
+use super::super::super::{
+    ContentEncoding, ContentLanguage, ContentType, KnownMagic, RainMetaDocumentV1Item,
+};
+use super::super::super::super::error::Error;
+
+/// Address list V1 meta, encoded as consecutive 20 bytes addresses
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AddressListV1(pub Vec<Address>);
+
+impl TryFrom<&[u8]> for AddressListV1 {
+    type Error = Error;
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        let chunks = value.chunks_exact(Address::len_bytes());
+        if !chunks.remainder().is_empty() {
+            return Err(Error::InvalidAddressListLength(value.len()));
+        }
+        Ok(AddressListV1(chunks.map(Address::from_slice).collect()))
+    }
+}
+
+impl TryFrom<RainMetaDocumentV1Item> for AddressListV1 {
+    type Error = Error;
+    fn try_from(value: RainMetaDocumentV1Item) -> Result<Self, Self::Error> {
+        if value.magic != KnownMagic::AddressList {
+            return Err(Error::UnsupportedMeta);
+        }
+        Self::try_from(value.unpack()?.as_slice())
+    }
+}
+
+impl From<AddressListV1> for RainMetaDocumentV1Item {
+    fn from(value: AddressListV1) -> Self {
+        RainMetaDocumentV1Item {
+            payload: ByteBuf::from(
+                value
+                    .0
+                    .iter()
+                    .flat_map(|address| address.0)
+                    .collect::<Vec<u8>>(),
+            ),
+            magic: KnownMagic::AddressList,
+            content_type: ContentType::OctetStream,
+            content_encoding: ContentEncoding::None,
+            content_language: ContentLanguage::None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() -> Result<(), Error> {
+        for count in [0u8, 1, 5] {
+            let list = AddressListV1((0..count).map(|i| Address::repeat_byte(i + 1)).collect());
+            let item = RainMetaDocumentV1Item::from(list.clone());
+            assert_eq!(item.payload.len(), count as usize * 20);
+
+            let decoded = RainMetaDocumentV1Item::cbor_decode(&item.cbor_encode()?)?;
+            assert_eq!(decoded.len(), 1);
+            assert_eq!(decoded[0].clone().unpack_into::<AddressListV1>()?, list);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_length() {
+        let mut item = RainMetaDocumentV1Item::from(AddressListV1(vec![Address::ZERO]));
+        item.payload.push(1);
+        assert!(matches!(
+            AddressListV1::try_from(item),
+            Err(Error::InvalidAddressListLength(21))
+        ));
+    }
+
+    #[test]
+    fn test_magic_mismatch() {
+        let mut item = RainMetaDocumentV1Item::from(AddressListV1::default());
+        item.magic = KnownMagic::DotrainV1;
+        assert!(matches!(
+            AddressListV1::try_from(item),
+            Err(Error::UnsupportedMeta)
+        ));
+    }
+}
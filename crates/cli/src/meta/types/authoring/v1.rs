@@ -22,6 +22,7 @@ pub type AuthoringMetaStructArray = sol!((bytes32, uint8, string)[]);
 pub struct AuthoringMeta(pub Vec<AuthoringMetaItem>);
 
 /// AuthoringMeta single item
+/// unknown json fields are ignored (not denied) so newer authoring meta jsons still parse
 #[derive(Validate, Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "json-schema", derive(JsonSchema))]
@@ -54,6 +55,7 @@ impl AuthoringMetaItem {
 
     // validates and abi encodes
     pub fn abi_encode_validate(&self) -> Result<Vec<u8>, Error> {
+        check_word_encoding(&self.word)?;
         self.validate()?;
         self.abi_encode()
     }
@@ -96,6 +98,9 @@ impl AuthoringMeta {
 
     /// abi encodes array of AuthoringMeta items after validating each
     pub fn abi_encode_validate(&self) -> Result<Vec<u8>, Error> {
+        for item in &self.0 {
+            check_word_encoding(&item.word)?;
+        }
         self.validate()?;
         self.abi_encode()
     }
@@ -131,6 +136,16 @@ impl AuthoringMeta {
     }
 }
 
+// words are packed into bytes32 byte by byte, so a multibyte character could be split
+// by the truncation and then fail to decode, only ascii words are safe
+fn check_word_encoding(word: &str) -> Result<(), Error> {
+    if word.is_ascii() {
+        Ok(())
+    } else {
+        Err(Error::InvalidWordEncoding(word.to_string()))
+    }
+}
+
 impl Validate for AuthoringMeta {
     fn validate(&self) -> Result<(), ValidationErrors> {
         for (index, item) in self.0.iter().enumerate() {
@@ -237,4 +252,67 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_deserialize_with_unknown_fields() -> Result<(), Error> {
+        let authoring_meta_content = r#"[
+            {
+                "word": "stack",
+                "description": "Copies an existing value from the stack.",
+                "operandParserOffset": 16,
+                "someNewField": { "inputs": 1, "outputs": 1 }
+            },
+            {
+                "word": "constant",
+                "operandParserOffset": 16,
+                "anotherNewField": "value"
+            }
+        ]"#;
+        let authoring_meta: AuthoringMeta = serde_json::from_str(authoring_meta_content)?;
+        let expected_authoring_meta = AuthoringMeta(vec![
+            AuthoringMetaItem {
+                word: "stack".to_string(),
+                operand_parser_offset: 16u8,
+                description: "Copies an existing value from the stack.".to_string(),
+            },
+            AuthoringMetaItem {
+                word: "constant".to_string(),
+                operand_parser_offset: 16u8,
+                description: String::new(),
+            },
+        ]);
+        assert_eq!(authoring_meta, expected_authoring_meta);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_word_encoding() -> Result<(), Error> {
+        let item = |word: &str| AuthoringMetaItem {
+            word: word.to_string(),
+            operand_parser_offset: 16,
+            description: String::new(),
+        };
+
+        let ascii = item("a-long-ascii-word-of-32-bytes-xy");
+        assert_eq!(ascii.word.len(), 32);
+        let encoded = ascii.abi_encode_validate()?;
+        assert_eq!(AuthoringMetaItem::abi_decode_validate(&encoded)?, ascii);
+
+        // 6 two byte characters, 37 bytes in total
+        let multibyte = item("wörd-wörd-wörd-wörd-wörd-wörd-w");
+        assert!(multibyte.word.len() > 32);
+        let error = multibyte.abi_encode_validate().unwrap_err();
+        assert!(matches!(&error, Error::InvalidWordEncoding(word) if *word == multibyte.word));
+        assert_eq!(
+            error.to_string(),
+            "word \"wörd-wörd-wörd-wörd-wörd-wörd-w\" must be ascii to be safely packed into bytes32"
+        );
+        assert!(matches!(
+            AuthoringMeta(vec![ascii, multibyte]).abi_encode_validate(),
+            Err(Error::InvalidWordEncoding(_))
+        ));
+
+        Ok(())
+    }
 }
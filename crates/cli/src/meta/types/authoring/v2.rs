@@ -8,14 +8,19 @@ use alloy::sol_types::private::Address;
 use alloy::sol;
 use rain_metaboard_subgraph::metaboard_client::*;
 use serde::{Deserialize, Serialize};
-use crate::meta::{KnownMagic, RainMetaDocumentV1Item};
+use crate::meta::{str_to_bytes32, KnownMagic, RainMetaDocumentV1Item};
+use super::v1::AuthoringMeta;
 use rain_metadata_bindings::IDescribedByMetaV1;
 use thiserror::Error;
 use super::super::super::implements_i_described_by_meta_v1;
 use typeshare::typeshare;
 
+#[cfg(feature = "json-schema")]
+use schemars::JsonSchema;
+
 #[typeshare]
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "json-schema", derive(JsonSchema))]
 pub struct AuthoringMetaV2Word {
     pub word: String,
     pub description: String,
@@ -23,6 +28,7 @@ pub struct AuthoringMetaV2Word {
 
 #[typeshare]
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "json-schema", derive(JsonSchema))]
 pub struct AuthoringMetaV2 {
     pub words: Vec<AuthoringMetaV2Word>,
 }
@@ -52,8 +58,18 @@ pub enum AuthoringMetaV2Error {
     MetaboardSubgraphError(
         #[from] rain_metaboard_subgraph::metaboard_client::MetaboardSubgraphClientError,
     ),
+    /// kept for compatibility, magic mismatches are reported as InvalidMetaMagic
     #[error("Meta bytes do not start with RainMetaDocumentV1 Magic")]
     MetaMagicNumberMismatch,
+    #[error(
+        "Meta magic number mismatch, expected {expected} ({}) but got {actual} ({})",
+        alloy::primitives::hex::encode_prefixed(.expected.to_prefix_bytes()),
+        alloy::primitives::hex::encode_prefixed(.actual.to_prefix_bytes())
+    )]
+    InvalidMetaMagic {
+        expected: KnownMagic,
+        actual: KnownMagic,
+    },
     #[error(transparent)]
     AbiDecodeError(#[from] alloy::sol_types::Error),
     #[error(transparent)]
@@ -62,6 +78,8 @@ pub enum AuthoringMetaV2Error {
     MetaError(#[from] crate::error::Error),
     #[error("Contract has no words")]
     HasNoWords,
+    #[error("Authoring meta is abi encoded as AuthoringMetaV1, expected AuthoringMetaV2 encoding")]
+    AuthoringMetaV1Encoding,
 }
 
 #[derive(Error, Debug)]
@@ -85,8 +103,18 @@ impl AuthoringMetaV2 {
     /// # Returns
     ///
     /// An AuthoringMetaV2 struct if successful, or an AuthoringMetaV2Error if an error occurs.
+    /// Errors with AuthoringMetaV1Encoding if the bytes are a valid AuthoringMetaV1
+    /// encoding instead.
     pub fn abi_decode(bytes: &[u8]) -> Result<Self, AuthoringMetaV2Error> {
-        let decoded = AuthoringMetasV2Sol::abi_decode(bytes, true)?;
+        let decoded = match AuthoringMetasV2Sol::abi_decode(bytes, true) {
+            Ok(v) => v,
+            Err(e) => {
+                if AuthoringMeta::abi_decode(bytes).is_ok() {
+                    return Err(AuthoringMetaV2Error::AuthoringMetaV1Encoding);
+                }
+                return Err(e.into());
+            }
+        };
 
         let mut words = Vec::new();
 
@@ -106,6 +134,18 @@ impl AuthoringMetaV2 {
         Ok(AuthoringMetaV2 { words })
     }
 
+    /// ABI encodes this AuthoringMetaV2, words longer than 32 bytes error.
+    pub fn abi_encode(&self) -> Result<Vec<u8>, AuthoringMetaV2Error> {
+        let mut items = Vec::new();
+        for item in &self.words {
+            items.push(AuthoringMetaV2Sol {
+                word: str_to_bytes32(&item.word)?.into(),
+                description: item.description.clone(),
+            });
+        }
+        Ok(AuthoringMetasV2Sol::abi_encode(&items))
+    }
+
     /// Fetches the authoring meta for a contract that implements IDescribedByMetaV1
     /// from the metaboard.
     ///
@@ -209,7 +249,10 @@ impl TryFrom<RainMetaDocumentV1Item> for AuthoringMetaV2 {
     type Error = AuthoringMetaV2Error;
     fn try_from(value: RainMetaDocumentV1Item) -> Result<Self, AuthoringMetaV2Error> {
         if value.magic != KnownMagic::AuthoringMetaV2 {
-            return Err(AuthoringMetaV2Error::MetaMagicNumberMismatch);
+            return Err(AuthoringMetaV2Error::InvalidMetaMagic {
+                expected: KnownMagic::AuthoringMetaV2,
+                actual: value.magic,
+            });
         }
         let payload = value.unpack()?;
         AuthoringMetaV2::abi_decode(&payload)
@@ -276,8 +319,15 @@ mod tests {
 
         let error = result.unwrap_err();
 
+        assert_eq!(
+            error.to_string(),
+            "Meta magic number mismatch, expected authoring-meta-v2 (0xff52fe42f1a05093) but got authoring-meta-v1 (0xffe9e3a02ca8e235)"
+        );
         match error {
-            AuthoringMetaV2Error::MetaMagicNumberMismatch => {}
+            AuthoringMetaV2Error::InvalidMetaMagic { expected, actual } => {
+                assert_eq!(expected, KnownMagic::AuthoringMetaV2);
+                assert_eq!(actual, KnownMagic::AuthoringMetaV1);
+            }
             _ => panic!("Unexpected error: {:?}", error),
         }
     }
@@ -299,6 +349,55 @@ mod tests {
         assert!(words[2].description == "description 3");
     }
 
+    #[test]
+    fn test_roundtrip() -> Result<(), AuthoringMetaV2Error> {
+        let authoring_meta = AuthoringMetaV2 {
+            words: vec![
+                AuthoringMetaV2Word {
+                    word: "stack".to_string(),
+                    description: "Copies an existing value from the stack.".to_string(),
+                },
+                AuthoringMetaV2Word {
+                    word: "constant".to_string(),
+                    description: "Copies a constant value onto the stack.".to_string(),
+                },
+            ],
+        };
+        let item = RainMetaDocumentV1Item {
+            payload: ByteBuf::from(authoring_meta.abi_encode()?),
+            magic: KnownMagic::AuthoringMetaV2,
+            content_type: ContentType::OctetStream,
+            content_encoding: ContentEncoding::Deflate,
+            content_language: ContentLanguage::None,
+        };
+        let item = RainMetaDocumentV1Item {
+            payload: ByteBuf::from(item.content_encoding.encode(&item.payload)),
+            ..item
+        };
+
+        let mut decoded = RainMetaDocumentV1Item::cbor_decode(&item.cbor_encode()?)?;
+        assert_eq!(decoded, vec![item]);
+        assert_eq!(
+            AuthoringMetaV2::try_from(decoded.pop().unwrap())?,
+            authoring_meta
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_abi_decode_v1_encoding() {
+        let v1 = AuthoringMeta(vec![crate::meta::types::authoring::v1::AuthoringMetaItem {
+            word: "stack".to_string(),
+            operand_parser_offset: 16,
+            description: "Copies an existing value from the stack.".to_string(),
+        }]);
+        let result = AuthoringMetaV2::abi_decode(&v1.abi_encode().unwrap());
+        assert!(matches!(
+            result,
+            Err(AuthoringMetaV2Error::AuthoringMetaV1Encoding)
+        ));
+    }
+
     #[tokio::test]
     async fn test_abi_decode_invalid() {
         let payload = decode::<String>("0x00".into()).unwrap();
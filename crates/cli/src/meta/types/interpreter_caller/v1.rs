@@ -1,6 +1,10 @@
+use regex::Regex;
 use validator::Validate;
+use once_cell::sync::Lazy;
+use std::collections::HashSet;
 use serde::{Serialize, Deserialize};
 use super::super::{
+    ValidateMeta,
     super::{RainMetaDocumentV1Item, Error},
     common::v1::{RainTitle, RainSymbol, RainString, Description, SolidityIdentifier},
 };
@@ -10,11 +14,16 @@ use schemars::JsonSchema;
 
 type AbiPath = RainString;
 
+/// Abi paths point into the abi of a method starting from one of its arguments,
+/// e.g. `[0].inputs[1]`
+pub static REGEX_ABI_PATH: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\[\d+\](\.[a-zA-Z$_][a-zA-Z0-9$_]*|\[\d+\])*$").unwrap());
+
 /// InterpreterCaller metadata used by Rainlang.
 /// Supports `IInterpreterCallerV2` Solidity contracts.
 /// Required info about a contract that receives expression in at least one of
 /// its methods.
-#[derive(Validate, Debug, Serialize, Deserialize)]
+#[derive(Validate, Debug, Serialize, Deserialize, PartialEq)]
 #[cfg_attr(feature = "json-schema", derive(JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
@@ -43,11 +52,59 @@ pub struct InterpreterCallerMeta {
     pub methods: Vec<Method>,
 }
 
+impl InterpreterCallerMeta {
+    /// structural checks that the json schema can't express, i.e. unique method names,
+    /// valid abi paths of method inputs and expressions and no duplicate paths within
+    /// a method, errors with InvalidMetaField naming the offending field
+    pub fn validate_structure(&self) -> Result<(), Error> {
+        let invalid = |field: String, reason: &str| Error::InvalidMetaField {
+            field,
+            reason: reason.to_string(),
+        };
+        let mut method_names = HashSet::new();
+        for (i, method) in self.methods.iter().enumerate() {
+            if !method_names.insert(&method.name.value) {
+                return Err(invalid(
+                    format!("methods[{}].name", i),
+                    "duplicate method name",
+                ));
+            }
+            let inputs = method.inputs.iter().enumerate().map(|(j, input)| {
+                (
+                    format!("methods[{}].inputs[{}].path", i, j),
+                    &input.path.value,
+                )
+            });
+            let expressions = method
+                .expressions
+                .iter()
+                .enumerate()
+                .map(|(j, expression)| {
+                    (
+                        format!("methods[{}].expressions[{}].path", i, j),
+                        &expression.path.value,
+                    )
+                });
+            let mut paths = vec![];
+            for (field, path) in inputs.chain(expressions) {
+                if !REGEX_ABI_PATH.is_match(path) {
+                    return Err(invalid(field, "must be an abi path, e.g. [0].inputs[1]"));
+                }
+                if paths.contains(&path) {
+                    return Err(invalid(field, "duplicate path"));
+                }
+                paths.push(path);
+            }
+        }
+        Ok(())
+    }
+}
+
 impl TryFrom<Vec<u8>> for InterpreterCallerMeta {
     type Error = Error;
     fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
         match serde_json::from_slice::<Self>(&value) {
-            Ok(t) => Ok(t.validate().map(|_| t)?),
+            Ok(t) => Ok(ValidateMeta::validate(&t).map(|_| t)?),
             Err(e) => Err(e)?,
         }
     }
@@ -57,7 +114,7 @@ impl TryFrom<&[u8]> for InterpreterCallerMeta {
     type Error = Error;
     fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
         match serde_json::from_slice::<Self>(value) {
-            Ok(t) => Ok(t.validate().map(|_| t)?),
+            Ok(t) => Ok(ValidateMeta::validate(&t).map(|_| t)?),
             Err(e) => Err(e)?,
         }
     }
@@ -70,7 +127,7 @@ impl TryFrom<RainMetaDocumentV1Item> for InterpreterCallerMeta {
     }
 }
 
-#[derive(Validate, Debug, Serialize, Deserialize)]
+#[derive(Validate, Debug, Serialize, Deserialize, PartialEq)]
 #[cfg_attr(feature = "json-schema", derive(JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
@@ -89,7 +146,7 @@ pub struct Method {
     pub expressions: Vec<Expression>,
 }
 
-#[derive(Validate, Debug, Serialize, Deserialize)]
+#[derive(Validate, Debug, Serialize, Deserialize, PartialEq)]
 #[cfg_attr(feature = "json-schema", derive(JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
@@ -105,7 +162,7 @@ pub struct MethodInput {
     pub path: AbiPath,
 }
 
-#[derive(Validate, Debug, Serialize, Deserialize)]
+#[derive(Validate, Debug, Serialize, Deserialize, PartialEq)]
 #[cfg_attr(feature = "json-schema", derive(JsonSchema))]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
@@ -129,7 +186,7 @@ pub struct Expression {
     pub context_columns: Vec<ContextColumn>,
 }
 
-#[derive(Validate, Debug, Serialize, Deserialize)]
+#[derive(Validate, Debug, Serialize, Deserialize, PartialEq)]
 #[cfg_attr(feature = "json-schema", derive(JsonSchema))]
 #[serde(deny_unknown_fields)]
 pub struct ContextColumn {
@@ -146,7 +203,7 @@ pub struct ContextColumn {
     pub cells: Vec<ContextCell>,
 }
 
-#[derive(Validate, Debug, Serialize, Deserialize)]
+#[derive(Validate, Debug, Serialize, Deserialize, PartialEq)]
 #[cfg_attr(feature = "json-schema", derive(JsonSchema))]
 #[serde(deny_unknown_fields)]
 pub struct ContextCell {
@@ -159,3 +216,80 @@ pub struct ContextCell {
     #[validate]
     pub alias: Option<RainSymbol>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::meta::KnownMagic;
+
+    fn caller_meta(input_path: &str, expression_path: &str) -> InterpreterCallerMeta {
+        serde_json::from_value(serde_json::json!({
+            "name": "Caller",
+            "abiName": "Caller",
+            "methods": [
+                {
+                    "name": "do something",
+                    "abiName": "doSomething",
+                    "inputs": [
+                        {
+                            "name": "config",
+                            "abiName": "config",
+                            "path": input_path
+                        }
+                    ],
+                    "expressions": [
+                        {
+                            "name": "calculate",
+                            "abiName": "calculate",
+                            "path": expression_path
+                        }
+                    ]
+                }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_roundtrip() -> Result<(), Error> {
+        let meta = caller_meta("[0].inputs[0]", "[0].evaluable");
+        ValidateMeta::validate(&meta)?;
+
+        let item = RainMetaDocumentV1Item::interpreter_caller(&meta)?;
+        let encoded = item.cbor_encode()?;
+        let decoded = RainMetaDocumentV1Item::cbor_decode(&encoded)?;
+        assert_eq!(decoded, vec![item]);
+        assert_eq!(decoded[0].magic, KnownMagic::InterpreterCallerMetaV1);
+        assert_eq!(InterpreterCallerMeta::try_from(decoded[0].clone())?, meta);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_structure() {
+        let meta = caller_meta("inputs[0]", "[0].evaluable");
+        let error = ValidateMeta::validate(&meta).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid methods[0].inputs[0].path: must be an abi path, e.g. [0].inputs[1]"
+        );
+        // passes the schema checks as the path is a valid rain string
+        assert!(validator::Validate::validate(&meta).is_ok());
+
+        let meta = caller_meta("[0].evaluable", "[0].evaluable");
+        assert!(matches!(
+            ValidateMeta::validate(&meta),
+            Err(Error::InvalidMetaField { field, .. }) if field == "methods[0].expressions[0].path"
+        ));
+        assert!(InterpreterCallerMeta::try_from(serde_json::to_vec(&meta).unwrap()).is_err());
+
+        let mut meta = caller_meta("[0].inputs[0]", "[0].evaluable");
+        let method =
+            serde_json::from_value(serde_json::to_value(&meta.methods[0]).unwrap()).unwrap();
+        meta.methods.push(method);
+        assert!(matches!(
+            ValidateMeta::validate(&meta),
+            Err(Error::InvalidMetaField { field, .. }) if field == "methods[1].name"
+        ));
+    }
+}
@@ -1,5 +1,6 @@
 //! All the known different Rain meta types and implementations
 
+pub mod address_list;
 pub mod authoring;
 pub mod common;
 pub mod dotrain;
@@ -9,3 +10,198 @@ pub mod op;
 pub mod rainlang;
 pub mod rainlangsource;
 pub mod solidity_abi;
+
+use super::super::error::Error;
+use authoring::v1::AuthoringMeta;
+use solidity_abi::v2::SolidityAbiMeta;
+use interpreter_caller::v1::InterpreterCallerMeta;
+use op::v1::OpMeta;
+use std::collections::BTreeSet;
+
+/// Common validation entry point for the known meta types
+pub trait ValidateMeta {
+    /// validates this meta, returning the first found error
+    fn validate(&self) -> Result<(), Error>;
+}
+
+impl ValidateMeta for AuthoringMeta {
+    fn validate(&self) -> Result<(), Error> {
+        Ok(validator::Validate::validate(self)?)
+    }
+}
+
+impl ValidateMeta for SolidityAbiMeta {
+    fn validate(&self) -> Result<(), Error> {
+        Ok(validator::Validate::validate(self)?)
+    }
+}
+
+impl ValidateMeta for OpMeta {
+    fn validate(&self) -> Result<(), Error> {
+        validator::Validate::validate(self)?;
+        self.validate_semantics()
+    }
+}
+
+impl ValidateMeta for InterpreterCallerMeta {
+    fn validate(&self) -> Result<(), Error> {
+        validator::Validate::validate(self)?;
+        self.validate_structure()
+    }
+}
+
+/// checks that the given op metas (one per opcode) and the authoring meta of the same
+/// deployer agree on the word set, op meta aliases are not part of the word set
+pub fn cross_validate(ops: &[OpMeta], authoring: &AuthoringMeta) -> Result<(), Error> {
+    let op_words: BTreeSet<&str> = ops.iter().map(|op| op.name.value.as_str()).collect();
+    let authoring_words: BTreeSet<&str> =
+        authoring.0.iter().map(|item| item.word.as_str()).collect();
+    if op_words == authoring_words {
+        Ok(())
+    } else {
+        Err(Error::WordSetMismatch(
+            op_words
+                .difference(&authoring_words)
+                .map(|word| word.to_string())
+                .collect(),
+            authoring_words
+                .difference(&op_words)
+                .map(|word| word.to_string())
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use authoring::v1::AuthoringMetaItem;
+
+    #[test]
+    fn test_validate_meta() -> Result<(), Error> {
+        let valid_authoring_meta = AuthoringMeta(vec![AuthoringMetaItem {
+            word: "stack".to_string(),
+            operand_parser_offset: 16,
+            description: "Copies an existing value from the stack.".to_string(),
+        }]);
+        let invalid_authoring_meta = AuthoringMeta(vec![AuthoringMetaItem {
+            word: "Stack".to_string(),
+            operand_parser_offset: 16,
+            description: "Copies an existing value from the stack.".to_string(),
+        }]);
+        let solidity_abi_meta: SolidityAbiMeta = serde_json::from_str("[]")?;
+        let interpreter_caller_meta: InterpreterCallerMeta = serde_json::from_str(
+            r#"{
+                "name": "Caller",
+                "abiName": "Caller",
+                "methods": [
+                    {
+                        "name": "do something",
+                        "abiName": "doSomething",
+                        "inputs": [
+                            {
+                                "name": "config",
+                                "abiName": "config",
+                                "path": "[0].inputs[0]"
+                            }
+                        ],
+                        "expressions": []
+                    }
+                ]
+            }"#,
+        )?;
+
+        let op_meta: OpMeta = serde_json::from_str(r#"{ "name": "stack", "aliases": ["copy"] }"#)?;
+
+        let metas: Vec<&dyn ValidateMeta> = vec![
+            &valid_authoring_meta,
+            &solidity_abi_meta,
+            &interpreter_caller_meta,
+            &op_meta,
+        ];
+        for meta in metas {
+            meta.validate()?;
+        }
+
+        assert!(matches!(
+            ValidateMeta::validate(&invalid_authoring_meta),
+            Err(Error::ValidationErrors(_))
+        ));
+
+        // op meta failing the schema and the semantic checks
+        let invalid_schema_op_meta: OpMeta = serde_json::from_str(r#"{ "name": "Stack" }"#)?;
+        assert!(matches!(
+            ValidateMeta::validate(&invalid_schema_op_meta),
+            Err(Error::ValidationErrors(_))
+        ));
+        let invalid_op_meta: OpMeta =
+            serde_json::from_str(r#"{ "name": "stack", "aliases": ["stack"] }"#)?;
+        assert!(matches!(
+            ValidateMeta::validate(&invalid_op_meta),
+            Err(Error::InvalidOpMeta(_))
+        ));
+
+        // interpreter caller meta failing the schema and the structural checks
+        let mut invalid_schema_caller_meta: InterpreterCallerMeta =
+            serde_json::from_value(serde_json::to_value(&interpreter_caller_meta)?)?;
+        invalid_schema_caller_meta.methods.clear();
+        assert!(matches!(
+            ValidateMeta::validate(&invalid_schema_caller_meta),
+            Err(Error::ValidationErrors(_))
+        ));
+        let invalid_caller_meta: InterpreterCallerMeta = serde_json::from_str(
+            &serde_json::to_string(&interpreter_caller_meta)?.replace("[0].inputs[0]", "inputs"),
+        )?;
+        assert!(matches!(
+            ValidateMeta::validate(&invalid_caller_meta),
+            Err(Error::InvalidMetaField { field, .. }) if field == "methods[0].inputs[0].path"
+        ));
+
+        // individually valid metas that don't agree on the word set
+        cross_validate(&[op_meta], &valid_authoring_meta)?;
+        let constant_op_meta: OpMeta = serde_json::from_str(r#"{ "name": "constant" }"#)?;
+        ValidateMeta::validate(&constant_op_meta)?;
+        assert!(matches!(
+            cross_validate(&[constant_op_meta], &valid_authoring_meta),
+            Err(Error::WordSetMismatch(..))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cross_validate() -> Result<(), Error> {
+        let ops: Vec<OpMeta> = serde_json::from_str(
+            r#"[
+                { "name": "stack", "aliases": ["copy"] },
+                { "name": "constant" },
+                { "name": "add" }
+            ]"#,
+        )?;
+        let authoring_meta = |words: &[&str]| {
+            AuthoringMeta(
+                words
+                    .iter()
+                    .map(|word| AuthoringMetaItem {
+                        word: word.to_string(),
+                        operand_parser_offset: 0,
+                        description: String::new(),
+                    })
+                    .collect(),
+            )
+        };
+
+        cross_validate(&ops, &authoring_meta(&["add", "constant", "stack"]))?;
+
+        let result = cross_validate(&ops, &authoring_meta(&["add", "stack", "sub", "copy"]));
+        match result {
+            Err(Error::WordSetMismatch(missing_in_authoring, missing_in_op)) => {
+                assert_eq!(missing_in_authoring, vec!["constant".to_string()]);
+                assert_eq!(missing_in_op, vec!["copy".to_string(), "sub".to_string()]);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use serde::{Serialize, Deserialize};
 use validator::{Validate, ValidationError, ValidationErrors};
 use super::super::{
@@ -130,11 +131,66 @@ pub struct OpMeta {
     pub aliases: Vec<RainSymbol>,
 }
 
+impl OpMeta {
+    /// semantic checks that the schema validation can't express, i.e. unique opcode name
+    /// and aliases, unique operand argument and input parameter names, non overlapping
+    /// operand argument bits and computed inputs specifying their bits, errors with
+    /// InvalidOpMeta naming the problem
+    pub fn validate_semantics(&self) -> Result<(), Error> {
+        let invalid = |reason: String| Err(Error::InvalidOpMeta(reason));
+
+        let mut names = HashSet::new();
+        for name in std::iter::once(&self.name).chain(&self.aliases) {
+            if !names.insert(&name.value) {
+                return invalid(format!("duplicate opcode name \"{}\"", name.value));
+            }
+        }
+
+        let mut arg_names = HashSet::new();
+        for (i, arg) in self.operand.iter().enumerate() {
+            if !arg_names.insert(&arg.name.value) {
+                return invalid(format!(
+                    "duplicate operand argument name \"{}\"",
+                    arg.name.value
+                ));
+            }
+            for other in &self.operand[..i] {
+                if arg.bits.0 <= other.bits.1 && other.bits.0 <= arg.bits.1 {
+                    return invalid(format!(
+                        "bits of operand arguments \"{}\" and \"{}\" overlap",
+                        other.name.value, arg.name.value
+                    ));
+                }
+            }
+        }
+
+        for (i, input) in self.inputs.iter().enumerate() {
+            let mut param_names = HashSet::new();
+            for param in &input.parameters {
+                if !param_names.insert(&param.name.value) {
+                    return invalid(format!(
+                        "duplicate parameter name \"{}\" in inputs[{}]",
+                        param.name.value, i
+                    ));
+                }
+            }
+            if input.computation.is_some() && input.bits.is_none() {
+                return invalid(format!("inputs[{}] has a computation but no bits", i));
+            }
+        }
+        Ok(())
+    }
+}
+
 impl TryFrom<Vec<u8>> for OpMeta {
     type Error = Error;
     fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
         match serde_json::from_slice::<Self>(&value) {
-            Ok(t) => Ok(t.validate().map(|_| t)?),
+            Ok(t) => {
+                Validate::validate(&t)?;
+                t.validate_semantics()?;
+                Ok(t)
+            }
             Err(e) => Err(e)?,
         }
     }
@@ -258,3 +314,81 @@ pub struct OperandArg {
     #[validate]
     pub valid_range: Option<Vec<OperandArgRange>>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::meta::{ContentEncoding, ContentLanguage, ContentType, KnownMagic};
+
+    fn op_meta_item(json: &str) -> RainMetaDocumentV1Item {
+        RainMetaDocumentV1Item {
+            payload: serde_bytes::ByteBuf::from(json.as_bytes()),
+            magic: KnownMagic::OpMetaV1,
+            content_type: ContentType::Json,
+            content_encoding: ContentEncoding::None,
+            content_language: ContentLanguage::None,
+        }
+    }
+
+    #[test]
+    fn test_validate() -> Result<(), Error> {
+        let valid = r#"{
+            "name": "call",
+            "desc": "Calls a source.",
+            "operand": [
+                { "name": "source-index", "bits": [0, 7] },
+                { "name": "outputs", "bits": [8, 11] }
+            ],
+            "inputs": [{
+                "parameters": [{ "name": "x" }, { "name": "y" }],
+                "bits": [12, 15],
+                "computation": "bits + 1"
+            }],
+            "outputs": [{ "Exact": 1 }],
+            "aliases": ["invoke"]
+        }"#;
+        let op_meta = op_meta_item(valid).unpack_into::<OpMeta>()?;
+        Validate::validate(&op_meta)?;
+        op_meta.validate_semantics()?;
+        assert_eq!(op_meta.name.value, "call");
+
+        let duplicate_name = r#"{ "name": "call", "aliases": ["invoke", "call"] }"#;
+        let error = OpMeta::try_from(duplicate_name.as_bytes().to_vec()).unwrap_err();
+        assert!(matches!(&error, Error::InvalidOpMeta(_)));
+        assert_eq!(
+            error.to_string(),
+            "invalid op meta: duplicate opcode name \"call\""
+        );
+        assert!(matches!(
+            op_meta_item(duplicate_name).unpack_into::<OpMeta>(),
+            Err(Error::UnpackFailed {
+                magic: KnownMagic::OpMetaV1,
+                ..
+            })
+        ));
+
+        let overlapping_bits = r#"{
+            "name": "call",
+            "operand": [
+                { "name": "source-index", "bits": [0, 7] },
+                { "name": "outputs", "bits": [7, 11] }
+            ]
+        }"#;
+        assert_eq!(
+            OpMeta::try_from(overlapping_bits.as_bytes().to_vec())
+                .unwrap_err()
+                .to_string(),
+            "invalid op meta: bits of operand arguments \"source-index\" and \"outputs\" overlap"
+        );
+
+        let missing_bits = r#"{ "name": "call", "inputs": [{ "computation": "bits + 1" }] }"#;
+        assert_eq!(
+            OpMeta::try_from(missing_bits.as_bytes().to_vec())
+                .unwrap_err()
+                .to_string(),
+            "invalid op meta: inputs[0] has a computation but no bits"
+        );
+
+        Ok(())
+    }
+}
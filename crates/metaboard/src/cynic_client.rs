@@ -30,12 +30,26 @@ pub trait CynicClient {
     ) -> Result<R, CynicClientError> {
         let request_body = R::build(variables);
 
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            url = %self.get_base_url(),
+            variables = %serde_json::to_string(&request_body.variables).unwrap_or_default(),
+            "sending subgraph query"
+        );
+
         let response = reqwest::Client::new()
             .post(self.get_base_url())
             .json(&request_body)
             .send()
             .await?;
 
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            url = %self.get_base_url(),
+            status = %response.status(),
+            "received subgraph response"
+        );
+
         let response_deserialized: GraphQlResponse<R> =
             response.json::<GraphQlResponse<R>>().await?;
 
@@ -45,3 +59,74 @@ pub trait CynicClient {
         }
     }
 }
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use super::*;
+    use crate::types::metas::{Bytes, MetasByHash, MetasByHashVariables};
+    use httpmock::{Method::POST, MockServer};
+    use std::sync::{Arc, Mutex};
+    use tracing::{field::Visit, Event, Subscriber};
+    use tracing_subscriber::{layer::Context, prelude::*, Layer};
+
+    struct TestClient(Url);
+
+    impl CynicClient for TestClient {
+        fn get_base_url(&self) -> Url {
+            self.0.clone()
+        }
+    }
+
+    // records the fields of all events as "name=value" strings
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<String>>>);
+
+    impl Visit for Capture {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("{}={:?}", field.name(), value));
+        }
+    }
+
+    impl<S: Subscriber> Layer<S> for Capture {
+        fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+            event.record(&mut self.clone());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_query_logging() {
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method(POST).path("/");
+            then.status(200).json_body_obj(&serde_json::json!({
+                "data": { "metaV1S": [] }
+            }));
+        });
+
+        let capture = Capture::default();
+        let _guard = tracing_subscriber::registry()
+            .with(capture.clone())
+            .set_default();
+
+        let client = TestClient(Url::parse(&server.url("/")).unwrap());
+        let _: MetasByHash = client
+            .query(MetasByHashVariables {
+                metahash: Some(Bytes("0x01".to_string())),
+                first: None,
+                skip: None,
+            })
+            .await
+            .unwrap();
+
+        let events = capture.0.lock().unwrap().clone();
+        assert!(events.contains(&"message=sending subgraph query".to_string()));
+        assert!(events
+            .iter()
+            .any(|e| e.starts_with("variables=") && e.contains("0x01")));
+        assert!(events.contains(&"message=received subgraph response".to_string()));
+        assert!(events.contains(&"status=200 OK".to_string()));
+    }
+}
@@ -1,7 +1,9 @@
 use crate::cynic_client::{CynicClient, CynicClientError};
 use crate::types::metas::*;
 use alloy::primitives::hex::{decode, encode, FromHexError};
+use alloy::primitives::Address;
 use reqwest::Url;
+use std::future::Future;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -22,6 +24,65 @@ pub enum MetaboardSubgraphClientError {
     },
 }
 
+/// Number of metas requested per page, same as the subgraph's default
+pub const PAGE_SIZE: i32 = 100;
+
+/// Queries pages of metas until a partial page is returned and returns the metas of
+/// all pages in order, key is the queried hash, subject or sender used in errors
+async fn query_all_metas<F, Fut>(
+    key: &str,
+    query_page: F,
+) -> Result<Vec<MetaV1>, MetaboardSubgraphClientError>
+where
+    F: Fn(i32) -> Fut,
+    Fut: Future<Output = Result<Vec<MetaV1>, CynicClientError>>,
+{
+    let mut all_metas = Vec::new();
+    let mut skip = 0;
+    loop {
+        let metas =
+            query_page(skip)
+                .await
+                .map_err(|e| MetaboardSubgraphClientError::CynicClientError {
+                    metahash: key.to_string(),
+                    source: e,
+                })?;
+        let count = metas.len();
+        all_metas.extend(metas);
+
+        if count < PAGE_SIZE as usize {
+            break;
+        }
+        skip += PAGE_SIZE;
+    }
+
+    if all_metas.is_empty() {
+        return Err(MetaboardSubgraphClientError::Empty(key.to_string()));
+    }
+    Ok(all_metas)
+}
+
+/// Same as query_all_metas() but decodes the meta bytes of the metas
+async fn query_all_pages<F, Fut>(
+    key: &str,
+    query_page: F,
+) -> Result<Vec<Vec<u8>>, MetaboardSubgraphClientError>
+where
+    F: Fn(i32) -> Fut,
+    Fut: Future<Output = Result<Vec<MetaV1>, CynicClientError>>,
+{
+    query_all_metas(key, query_page)
+        .await?
+        .into_iter()
+        .map(|meta| {
+            decode(&meta.meta.0).map_err(|e| MetaboardSubgraphClientError::FromHexError {
+                metahash: key.to_string(),
+                source: e,
+            })
+        })
+        .collect()
+}
+
 pub struct MetaboardSubgraphClient {
     url: Url,
 }
@@ -37,40 +98,63 @@ impl MetaboardSubgraphClient {
         Self { url }
     }
 
-    /// Find all metas with a given hash
+    /// Find all metas with a given hash, pages through the results until the
+    /// subgraph returns a partial page
     pub async fn get_metabytes_by_hash(
         &self,
         metahash: &[u8; 32],
     ) -> Result<Vec<Vec<u8>>, MetaboardSubgraphClientError> {
         let hex_string = encode(metahash);
-        let metahash = format!("0x{}", hex_string);
+        let metahash = &format!("0x{}", hex_string);
 
-        let data = self
-            .query::<MetasByHash, MetasByHashVariables>(MetasByHashVariables {
+        query_all_pages(metahash, |skip| async move {
+            self.query::<MetasByHash, MetasByHashVariables>(MetasByHashVariables {
                 metahash: Some(Bytes(metahash.clone())),
+                first: Some(PAGE_SIZE),
+                skip: Some(skip),
             })
             .await
-            .map_err(|e| MetaboardSubgraphClientError::CynicClientError {
-                metahash: metahash.clone(),
-                source: e,
-            })?;
+            .map(|data| data.meta_v1_s)
+        })
+        .await
+    }
 
-        if data.meta_v1_s.is_empty() {
-            return Err(MetaboardSubgraphClientError::Empty(metahash));
-        }
+    /// Find all metas with a given subject, pages through the results until the
+    /// subgraph returns a partial page
+    pub async fn get_all_metabytes_by_subject(
+        &self,
+        subject: &BigInt,
+    ) -> Result<Vec<Vec<u8>>, MetaboardSubgraphClientError> {
+        query_all_pages(&subject.0, |skip| async move {
+            self.query::<MetasBySubject, MetasBySubjectVariables>(MetasBySubjectVariables {
+                subject: Some(subject.clone()),
+                first: Some(PAGE_SIZE),
+                skip: Some(skip),
+            })
+            .await
+            .map(|data| data.meta_v1_s)
+        })
+        .await
+    }
 
-        // decode all the metas
-        let mut meta_bytes = Vec::new();
-        for meta in data.meta_v1_s {
-            meta_bytes.push(decode(&meta.meta.0).map_err(|e| {
-                MetaboardSubgraphClientError::FromHexError {
-                    metahash: metahash.clone(),
-                    source: e,
-                }
-            })?);
-        }
+    /// Find all metas emitted by a given sender, returns the full records, pages
+    /// through the results until the subgraph returns a partial page
+    pub async fn get_metas_by_sender(
+        &self,
+        sender: &Address,
+    ) -> Result<Vec<MetaV1>, MetaboardSubgraphClientError> {
+        let sender = &format!("0x{}", encode(sender));
 
-        Ok(meta_bytes)
+        query_all_metas(sender, |skip| async move {
+            self.query::<MetasBySender, MetasBySenderVariables>(MetasBySenderVariables {
+                sender: Some(Bytes(sender.clone())),
+                first: Some(PAGE_SIZE),
+                skip: Some(skip),
+            })
+            .await
+            .map(|data| data.meta_v1_s)
+        })
+        .await
     }
 }
 
@@ -165,4 +249,189 @@ mod tests {
             _ => panic!("Unexpected result: {:?}", result),
         }
     }
+
+    #[tokio::test]
+    async fn test_get_metas_by_sender_success() {
+        let server = MockServer::start_async().await;
+        let url = Url::parse(&server.url("/")).unwrap();
+
+        let sender = Address::repeat_byte(0x11);
+
+        server.mock(|when, then| {
+            when.method(POST).path("/").body_contains(encode(sender));
+            then.status(200).json_body_obj(&{
+                serde_json::json!({
+                    "data": {
+                        "metaV1S": [
+                            {
+                                "meta": "0x01",
+                                "metaHash": "0xaa",
+                                "sender": format!("0x{}", encode(sender)),
+                                "id": "0x00",
+                                "metaBoard": {
+                                    "id": "0x00",
+                                    "metas": [],
+                                    "address": "0x00",
+                                },
+                                "subject": "1",
+                            },
+                            {
+                                "meta": "0x02",
+                                "metaHash": "0xbb",
+                                "sender": format!("0x{}", encode(sender)),
+                                "id": "0x01",
+                                "metaBoard": {
+                                    "id": "0x00",
+                                    "metas": [],
+                                    "address": "0x00",
+                                },
+                                "subject": "2",
+                            }
+                        ]
+                    }
+                })
+            });
+        });
+
+        let client = MetaboardSubgraphClient::new(url);
+
+        let result = client.get_metas_by_sender(&sender).await.unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].meta.0, "0x01");
+        assert_eq!(result[0].meta_hash.0, "0xaa");
+        assert_eq!(result[0].subject.0, "1");
+        assert_eq!(result[1].meta.0, "0x02");
+        assert_eq!(result[1].meta_hash.0, "0xbb");
+        assert_eq!(result[1].subject.0, "2");
+        assert_eq!(result[1].sender.0, format!("0x{}", encode(sender)));
+    }
+
+    #[tokio::test]
+    async fn test_get_metas_by_sender_empty() {
+        let server = MockServer::start_async().await;
+        let url = Url::parse(&server.url("/")).unwrap();
+
+        server.mock(|when, then| {
+            when.method(POST).path("/").body_contains("sender");
+            then.status(200).json_body_obj(&{
+                serde_json::json!({
+                    "data": {
+                        "metaV1S": []
+                    }
+                })
+            });
+        });
+
+        let client = MetaboardSubgraphClient::new(url);
+
+        let result = client.get_metas_by_sender(&Address::ZERO).await;
+        match result {
+            Err(MetaboardSubgraphClientError::Empty(_)) => (),
+            _ => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_metas_by_sender_pages() {
+        let server = MockServer::start_async().await;
+        let url = Url::parse(&server.url("/")).unwrap();
+
+        let sender = Address::repeat_byte(0x11);
+        let meta = |i: usize| {
+            serde_json::json!({
+                "meta": format!("0x{:04x}", i),
+                "metaHash": "0x00",
+                "sender": format!("0x{}", encode(sender)),
+                "id": format!("0x{:04x}", i),
+                "metaBoard": {
+                    "id": "0x00",
+                    "metas": [],
+                    "address": "0x00",
+                },
+                "subject": "1",
+            })
+        };
+        let first_page: Vec<_> = (0..PAGE_SIZE as usize).map(meta).collect();
+        let second_page: Vec<_> = (PAGE_SIZE as usize..PAGE_SIZE as usize + 2)
+            .map(meta)
+            .collect();
+
+        let first_mock = server.mock(|when, then| {
+            when.method(POST).path("/").body_contains(r#""skip":0"#);
+            then.status(200).json_body_obj(&serde_json::json!({
+                "data": { "metaV1S": first_page }
+            }));
+        });
+        let second_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .body_contains(format!(r#""skip":{}"#, PAGE_SIZE));
+            then.status(200).json_body_obj(&serde_json::json!({
+                "data": { "metaV1S": second_page }
+            }));
+        });
+
+        let client = MetaboardSubgraphClient::new(url);
+        let result = client.get_metas_by_sender(&sender).await.unwrap();
+
+        first_mock.assert();
+        second_mock.assert();
+        assert_eq!(result.len(), PAGE_SIZE as usize + 2);
+        for (i, meta) in result.iter().enumerate() {
+            assert_eq!(meta.id.inner(), format!("0x{:04x}", i));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_all_metabytes_by_subject_pages() {
+        let server = MockServer::start_async().await;
+        let url = Url::parse(&server.url("/")).unwrap();
+
+        let meta = |i: usize| {
+            serde_json::json!({
+                "meta": format!("0x{:04x}", i),
+                "metaHash": "0x00",
+                "sender": "0x00",
+                "id": format!("0x{:04x}", i),
+                "metaBoard": {
+                    "id": "0x00",
+                    "metas": [],
+                    "address": "0x00",
+                },
+                "subject": "1",
+            })
+        };
+        let first_page: Vec<_> = (0..PAGE_SIZE as usize).map(meta).collect();
+        let second_page: Vec<_> = (PAGE_SIZE as usize..PAGE_SIZE as usize + 3)
+            .map(meta)
+            .collect();
+
+        let first_mock = server.mock(|when, then| {
+            when.method(POST).path("/").body_contains(r#""skip":0"#);
+            then.status(200).json_body_obj(&serde_json::json!({
+                "data": { "metaV1S": first_page }
+            }));
+        });
+        let second_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/")
+                .body_contains(format!(r#""skip":{}"#, PAGE_SIZE));
+            then.status(200).json_body_obj(&serde_json::json!({
+                "data": { "metaV1S": second_page }
+            }));
+        });
+
+        let client = MetaboardSubgraphClient::new(url);
+        let result = client
+            .get_all_metabytes_by_subject(&BigInt("1".to_string()))
+            .await
+            .unwrap();
+
+        first_mock.assert();
+        second_mock.assert();
+        assert_eq!(result.len(), PAGE_SIZE as usize + 3);
+        for (i, bytes) in result.iter().enumerate() {
+            assert_eq!(bytes, &(i as u16).to_be_bytes().to_vec());
+        }
+    }
 }
@@ -2,12 +2,42 @@ use crate::schema;
 #[derive(cynic::QueryVariables, Debug)]
 pub struct MetasByHashVariables {
     pub metahash: Option<Bytes>,
+    pub first: Option<i32>,
+    pub skip: Option<i32>,
 }
 
 #[derive(cynic::QueryFragment, Debug)]
 #[cynic(graphql_type = "Query", variables = "MetasByHashVariables")]
 pub struct MetasByHash {
-    #[arguments(where: { metaHash: $metahash })]
+    #[arguments(where: { metaHash: $metahash }, first: $first, skip: $skip)]
+    pub meta_v1_s: Vec<MetaV1>,
+}
+
+#[derive(cynic::QueryVariables, Debug)]
+pub struct MetasBySubjectVariables {
+    pub subject: Option<BigInt>,
+    pub first: Option<i32>,
+    pub skip: Option<i32>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "Query", variables = "MetasBySubjectVariables")]
+pub struct MetasBySubject {
+    #[arguments(where: { subject: $subject }, first: $first, skip: $skip)]
+    pub meta_v1_s: Vec<MetaV1>,
+}
+
+#[derive(cynic::QueryVariables, Debug)]
+pub struct MetasBySenderVariables {
+    pub sender: Option<Bytes>,
+    pub first: Option<i32>,
+    pub skip: Option<i32>,
+}
+
+#[derive(cynic::QueryFragment, Debug)]
+#[cynic(graphql_type = "Query", variables = "MetasBySenderVariables")]
+pub struct MetasBySender {
+    #[arguments(where: { sender: $sender }, first: $first, skip: $skip)]
     pub meta_v1_s: Vec<MetaV1>,
 }
 